@@ -0,0 +1,33 @@
+use crate::node::NodeKind;
+
+/// Error returned by fallible accessors like [`Signal::try_get`](crate::Signal::try_get).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The node has already been disposed, so its handle is stale.
+    Disposed(NodeKind),
+    /// The handle belongs to another runtime than the current one,
+    /// or to a runtime already dropped.
+    WrongRuntime(NodeKind),
+    /// The computed is read while computing itself, directly or through
+    /// other computeds. Holds the labels of the cycle from the computed to
+    /// itself, each the name given by `.named()` or the kind and address.
+    Cycle(Vec<String>),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disposed(kind) => write!(f, "this `{kind}` has already been disposed"),
+            Self::WrongRuntime(kind) => {
+                write!(f, "this `{kind}` is used out of the runtime it belongs to")
+            }
+            Self::Cycle(path) => {
+                write!(f, "this `Computed` is read while computing itself: ")?;
+                write!(f, "{}", path.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,254 @@
+use crate::node::CleanupKey;
+use crate::{Effect, NodeKind, Signal, dispose_oper, get_active_sub, runtime, untracked};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// Future spawned to the executor given to [`async_computed`] or [`spawn_effect`]
+pub type SpawnedFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// State of an [`AsyncComputed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsyncState<T, E> {
+    Pending,
+    Ready(T),
+    Err(E),
+}
+impl<T, E> AsyncState<T, E> {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending)
+    }
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+    pub fn err(&self) -> Option<&E> {
+        match self {
+            Self::Err(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Create an [`AsyncComputed`], running the future returned by `f` on `spawn`.
+///
+/// Signals and computeds read in `f` before returning the future are tracked,
+/// and when they change, `f` is called again with the previous ready value
+/// and the previous future is cancelled (dropped). Reads in the future are not tracked.
+///
+/// ```
+/// use alien_signals::{signal, async_computed, AsyncState};
+/// use futures::{executor::LocalPool, task::LocalSpawnExt};
+///
+/// let mut pool = LocalPool::new();
+/// let spawner = pool.spawner();
+///
+/// let id = signal(1);
+/// let user = async_computed(
+///     move |future| spawner.spawn_local(future).unwrap(),
+///     move |_prev| {
+///         let id = id.get();
+///         async move { Ok::<_, ()>(format!("user {id}")) }
+///     },
+/// );
+/// assert_eq!(user.get(), AsyncState::Pending);
+///
+/// pool.run_until_stalled();
+/// assert_eq!(user.get(), AsyncState::Ready("user 1".into()));
+///
+/// id.set(2);
+/// assert_eq!(user.get(), AsyncState::Pending);
+/// pool.run_until_stalled();
+/// assert_eq!(user.get(), AsyncState::Ready("user 2".into()));
+/// ```
+#[track_caller]
+pub fn async_computed<T: 'static, E: 'static, F: Future<Output = Result<T, E>> + 'static>(
+    spawn: impl Fn(SpawnedFuture) + 'static,
+    f: impl Fn(Option<T>) -> F + 'static,
+) -> AsyncComputed<T, E> {
+    let state = Signal::new_with_eq_fn(AsyncState::Pending, |_, _| false);
+    let effect = Effect::new(move || {
+        let prev = untracked(|| {
+            if state.with(AsyncState::is_pending) {
+                return None;
+            }
+            let mut prev = None;
+            state.set_mut(|state| {
+                if let AsyncState::Ready(value) = std::mem::replace(state, AsyncState::Pending) {
+                    prev = Some(value);
+                }
+            });
+            prev
+        });
+        spawn_cancellable(&spawn, f(prev), move |result| {
+            state.set(match result {
+                Ok(value) => AsyncState::Ready(value),
+                Err(error) => AsyncState::Err(error),
+            });
+        });
+    });
+    AsyncComputed {
+        state,
+        effect: effect.node.into(),
+    }
+}
+
+/// Handle of the state of an async computed, created by [`async_computed`].
+pub struct AsyncComputed<T, E> {
+    state: Signal<AsyncState<T, E>>,
+    effect: crate::Node,
+}
+impl<T, E> Clone for AsyncComputed<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T, E> Copy for AsyncComputed<T, E> {}
+impl<T: 'static, E: 'static> AsyncComputed<T, E> {
+    /// ## Panics
+    ///
+    /// if this async computed has been disposed
+    #[track_caller]
+    pub fn get(&self) -> AsyncState<T, E>
+    where
+        T: Clone,
+        E: Clone,
+    {
+        self.state.get()
+    }
+
+    /// see [`Signal::with`]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&AsyncState<T, E>) -> R) -> R {
+        self.state.with(f)
+    }
+
+    #[track_caller]
+    pub fn is_pending(&self) -> bool {
+        self.with(AsyncState::is_pending)
+    }
+
+    /// Stop tracking and cancel the running future.
+    /// Using this async computed or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        dispose_oper(self.effect, NodeKind::Effect);
+        self.state.dispose();
+    }
+}
+
+/// Create an effect spawning the future returned by `f` on `spawn`.
+///
+/// Like [`async_computed`], reads in `f` before returning the future are tracked,
+/// and the previous future is cancelled when it's re-run or disposed.
+#[track_caller]
+pub fn spawn_effect<F: Future<Output = ()> + 'static>(
+    spawn: impl Fn(SpawnedFuture) + 'static,
+    f: impl Fn() -> F + 'static,
+) -> Effect {
+    Effect::new(move || spawn_cancellable(&spawn, f(), |()| ()))
+}
+
+/// Spawn `future`, that is cancelled by the cleanup of the current effect.
+/// `on_ready` is called with the output unless cancelled.
+pub(crate) fn spawn_cancellable<T: 'static>(
+    spawn: &dyn Fn(SpawnedFuture),
+    future: impl Future<Output = T> + 'static,
+    on_ready: impl FnOnce(T) + 'static,
+) {
+    spawn_cancellable_by(get_active_sub(), spawn, future, on_ready);
+}
+
+/// Spawn `future`, that is cancelled by the cleanup of `owner`.
+/// `on_ready` is called with the output unless cancelled.
+pub(crate) fn spawn_cancellable_by<T: 'static>(
+    owner: Option<crate::Node>,
+    spawn: &dyn Fn(SpawnedFuture),
+    future: impl Future<Output = T> + 'static,
+    on_ready: impl FnOnce(T) + 'static,
+) {
+    let cancel = Rc::new(Cancel::default());
+    let unregister = Unregister(owner.map(|owner| {
+        let key = owner.push_cleanup(Box::new({
+            let cancel = cancel.clone();
+            move || cancel.cancel()
+        }));
+        (owner, key)
+    }));
+    let cancellable = Cancellable {
+        future: Some(Box::pin(future)),
+        cancel,
+    };
+    spawn(Box::pin(async move {
+        let _unregister = unregister;
+        if let Some(output) = cancellable.await {
+            untracked(|| on_ready(output));
+        }
+    }));
+}
+
+/// Removes the cleanup cancelling a future from its owner when the future
+/// completes or is dropped, not to pile them up on a long-lived owner
+struct Unregister(Option<(crate::Node, CleanupKey)>);
+impl Drop for Unregister {
+    fn drop(&mut self) {
+        let Some((owner, key)) = self.0 else {
+            return;
+        };
+        // the future may be dropped out of the runtime of `owner`
+        if runtime::is_accessible() && owner.is_in_current_runtime() && !owner.is_disposed() {
+            drop(owner.remove_cleanup(key));
+        }
+    }
+}
+
+#[derive(Default)]
+struct Cancel {
+    is_cancelled: std::cell::Cell<bool>,
+    waker: std::cell::RefCell<Option<Waker>>,
+}
+impl Cancel {
+    fn cancel(&self) {
+        self.is_cancelled.set(true);
+        // wake up the task to drop the future as soon as possible
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Cancellable<T> {
+    future: Option<Pin<Box<dyn Future<Output = T>>>>,
+    cancel: Rc<Cancel>,
+}
+impl<T> Future for Cancellable<T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.cancel.is_cancelled.get() {
+            self.future = None;
+            return Poll::Ready(None);
+        }
+        let Some(future) = self.future.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match untracked(|| future.as_mut().poll(cx)) {
+            // cancelled while polling, e.g. by the future setting a dependency of the effect
+            Poll::Ready(_) if self.cancel.is_cancelled.get() => {
+                self.future = None;
+                Poll::Ready(None)
+            }
+            Poll::Ready(output) => {
+                self.future = None;
+                Poll::Ready(Some(output))
+            }
+            Poll::Pending => {
+                *self.cancel.waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
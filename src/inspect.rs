@@ -0,0 +1,427 @@
+//! Read-only introspection of the dependency graph, for debugging and tooling.
+//!
+//! ```
+//! use alien_signals::{Signal, Computed, NodeKind};
+//! use alien_signals::inspect::{deps_of, subscribers_of, kind_of, walk, AsNodeId};
+//!
+//! let a = Signal::new(1);
+//! let b = Signal::new(2);
+//! let sum = Computed::new(move |_| a.get() + b.get());
+//! assert_eq!(sum.get(), 3);
+//!
+//! assert_eq!(deps_of(&sum), [a.node_id(), b.node_id()]);
+//! assert_eq!(subscribers_of(&a), [sum.node_id()]);
+//! assert_eq!(kind_of(&sum), NodeKind::Computed);
+//!
+//! let mut signals = 0;
+//! walk(|node| if node.kind() == NodeKind::Signal { signals += 1 });
+//! assert_eq!(signals, 2);
+//! ```
+//!
+//! Nothing here tracks or changes the graph: reading the links doesn't
+//! subscribe the current effect, nor does it re-run stale computeds.
+
+use crate::node::NodeContext;
+use crate::{Computed, Effect, EffectHandle, EffectScope, Flags, Node, NodeKind, Signal};
+
+/// Identity of a node in the graph, comparable across handle types.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(pub(crate) Node);
+
+impl std::fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NodeId({:#x})", self.0.addr())
+    }
+}
+
+/// Handles whose nodes can be inspected
+pub trait AsNodeId {
+    fn node_id(&self) -> NodeId;
+}
+impl AsNodeId for NodeId {
+    fn node_id(&self) -> NodeId {
+        *self
+    }
+}
+impl<T> AsNodeId for Signal<T> {
+    fn node_id(&self) -> NodeId {
+        NodeId(self.0.into())
+    }
+}
+impl<T> AsNodeId for Computed<T> {
+    fn node_id(&self) -> NodeId {
+        NodeId(self.0.into())
+    }
+}
+impl AsNodeId for Effect {
+    fn node_id(&self) -> NodeId {
+        NodeId(self.node.into())
+    }
+}
+impl AsNodeId for EffectScope {
+    fn node_id(&self) -> NodeId {
+        NodeId(self.node)
+    }
+}
+impl AsNodeId for EffectHandle {
+    fn node_id(&self) -> NodeId {
+        NodeId(self.node)
+    }
+}
+impl AsNodeId for Node<NodeContext> {
+    fn node_id(&self) -> NodeId {
+        NodeId(*self)
+    }
+}
+
+impl NodeId {
+    /// Whether the node has been disposed.
+    ///
+    /// ## Panics
+    ///
+    /// if the node belongs to another runtime than the current one
+    #[track_caller]
+    pub fn is_disposed(self) -> bool {
+        if !self.0.is_in_current_runtime() {
+            panic!("this node is used out of the runtime it belongs to")
+        }
+        self.0.is_disposed()
+    }
+
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[track_caller]
+    pub fn kind(self) -> NodeKind {
+        self.assert_live();
+        self.0.node_kind()
+    }
+
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[track_caller]
+    pub fn flags(self) -> Flags {
+        self.assert_live();
+        self.0.flags_unchecked()
+    }
+
+    /// The name given by `.named()`, like [`Signal::named`]
+    ///
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[track_caller]
+    pub fn name(self) -> Option<String> {
+        self.assert_live();
+        self.0.name().map(String::from)
+    }
+
+    /// Nodes this node depends on, in the order they're tracked.
+    /// For effects and effect scopes, they include the effects and scopes owned by it.
+    ///
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[track_caller]
+    pub fn deps(self) -> Vec<NodeId> {
+        self.assert_live();
+        std::iter::successors(self.0.deps(), |link| link.next_dep())
+            .map(|link| link.dep())
+            .filter(|dep| !dep.is_disposed())
+            .map(NodeId)
+            .collect()
+    }
+
+    /// Nodes subscribing this node, in the order they subscribed.
+    ///
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[track_caller]
+    pub fn subscribers(self) -> Vec<NodeId> {
+        self.assert_live();
+        std::iter::successors(self.0.subs(), |link| link.next_sub())
+            .map(|link| link.sub())
+            .filter(|sub| !sub.is_disposed())
+            .map(NodeId)
+            .collect()
+    }
+
+    /// Number of the cleanups registered on this node and not run yet.
+    /// Not a part of the API, only for the tests of this crate to check
+    /// that cleanups don't pile up on a long-lived signal.
+    ///
+    /// ## Panics
+    ///
+    /// if the node has been disposed or belongs to another runtime
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn cleanup_count(self) -> usize {
+        self.assert_live();
+        self.0.cleanup_count()
+    }
+
+    #[track_caller]
+    fn assert_live(self) {
+        if self.is_disposed() {
+            panic!("this node has already been disposed")
+        }
+    }
+}
+
+/// see [`NodeId::kind`]
+#[track_caller]
+pub fn kind_of(node: &impl AsNodeId) -> NodeKind {
+    node.node_id().kind()
+}
+
+/// see [`NodeId::flags`]
+#[track_caller]
+pub fn flags_of(node: &impl AsNodeId) -> Flags {
+    node.node_id().flags()
+}
+
+/// see [`NodeId::name`]
+#[track_caller]
+pub fn name_of(node: &impl AsNodeId) -> Option<String> {
+    node.node_id().name()
+}
+
+/// see [`NodeId::deps`]
+#[track_caller]
+pub fn deps_of(node: &impl AsNodeId) -> Vec<NodeId> {
+    node.node_id().deps()
+}
+
+/// see [`NodeId::subscribers`]
+#[track_caller]
+pub fn subscribers_of(node: &impl AsNodeId) -> Vec<NodeId> {
+    node.node_id().subscribers()
+}
+
+/// Whether `node` is alive, i.e. not disposed and in the current runtime.
+pub fn is_alive(node: &impl AsNodeId) -> bool {
+    let NodeId(node) = node.node_id();
+    node.is_in_current_runtime() && !node.is_disposed()
+}
+
+/// Visit every node of the current runtime that is not disposed.
+///
+/// To walk the graph of another [`Runtime`](crate::Runtime), call this in
+/// its `enter`. Nodes disposed by `f` are skipped, and nodes created by `f`
+/// are not visited.
+pub fn walk(mut f: impl FnMut(NodeId)) {
+    for node in Node::live_nodes() {
+        if !node.is_disposed() {
+            f(NodeId(node));
+        }
+    }
+}
+
+/// Whether [`Effect::last_cause`] records causes, that is whether the
+/// `causality` feature is enabled.
+pub const RECORDS_CAUSES: bool = cfg!(feature = "causality");
+
+/// Why an effect ran, returned by [`Effect::last_cause`]: `signal` was set
+/// at `location`, changing the computeds `via` read by the effect.
+///
+/// Displayed like `"price" set at src/main.rs:9:7 -> "total"`, labelling
+/// nodes by the names given by `.named()`, or by their kinds if unnamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cause {
+    /// the signal set, or read in [`trigger`](crate::trigger)
+    pub signal: NodeId,
+    /// the computeds from `signal` to the effect, nearest to `signal` first
+    pub via: Vec<NodeId>,
+    /// where `signal` was set or triggered, if known
+    pub location: Option<&'static std::panic::Location<'static>>,
+}
+
+impl std::fmt::Display for Cause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_label(f, self.signal)?;
+        if let Some(location) = self.location {
+            write!(f, " set at {location}")?;
+        }
+        for node in &self.via {
+            write!(f, " -> ")?;
+            write_label(f, *node)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_label(f: &mut std::fmt::Formatter<'_>, node: NodeId) -> std::fmt::Result {
+    if !is_alive(&node) {
+        return write!(f, "<disposed>");
+    }
+    match node.name() {
+        Some(name) => write!(f, "{name:?}"),
+        None => write!(f, "{}", node.kind()),
+    }
+}
+
+/// Output format of [`export_graph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// [Graphviz](https://graphviz.org) DOT, with edges from each dependency to its subscriber
+    Dot,
+    /// `{"nodes": [{"id", "kind", "label", "flags"}], "links": [{"dep", "sub", "version"}]}`,
+    /// where `label` is the name of the node or `null`, and `dep` and `sub` refer to `id` of the nodes
+    Json,
+}
+
+/// Dump the whole graph of the current runtime in `format`, e.g. to render it
+/// with `dot -Tsvg` while debugging glitches.
+///
+/// Node ids are assigned in the order of [`walk`], so they're stable
+/// as long as the same nodes are created in the same order.
+///
+/// ```
+/// use alien_signals::{Signal, Computed};
+/// use alien_signals::inspect::{export_graph, Format};
+///
+/// let count = Signal::new(1);
+/// let double = Computed::new(move |_| count.get() * 2);
+/// assert_eq!(double.get(), 2);
+///
+/// let dot = export_graph(Format::Dot);
+/// assert!(dot.starts_with("digraph {"));
+/// assert!(dot.contains("n0 -> n1"));
+/// ```
+pub fn export_graph(format: Format) -> String {
+    let mut nodes = Vec::new();
+    walk(|node| nodes.push(node));
+    let ids = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, node)| (*node, id))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let mut links = Vec::new();
+    for (sub, node) in nodes.iter().enumerate() {
+        for link in std::iter::successors(node.0.deps(), |link| link.next_dep()) {
+            if let Some(&dep) = ids.get(&NodeId(link.dep())) {
+                links.push((dep, sub, link.version().get()));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    match format {
+        Format::Dot => write_dot(&mut out, &nodes, &links),
+        Format::Json => write_json(&mut out, &nodes, &links),
+    }
+    .expect("writing to `String` never fails");
+    out
+}
+
+fn write_dot(
+    out: &mut String,
+    nodes: &[NodeId],
+    links: &[(usize, usize, usize)],
+) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    writeln!(out, "digraph {{")?;
+    for (id, node) in nodes.iter().enumerate() {
+        let kind = node.kind();
+        let flags = node.flags();
+        write!(out, "    n{id} [label=\"")?;
+        if let Some(name) = node.name() {
+            write_dot_escaped(out, &name)?;
+            write!(out, "\\n")?;
+        }
+        write!(out, "{kind} #{id}")?;
+        if !flags.is_zero() {
+            write!(out, "\\n{}", flags.names().collect::<Vec<_>>().join(" | "))?;
+        }
+        let shape = match kind {
+            NodeKind::Signal => "ellipse",
+            NodeKind::Computed => "box",
+            NodeKind::Effect => "hexagon",
+            NodeKind::EffectScope => "folder",
+            NodeKind::Trigger => "diamond",
+        };
+        write!(out, "\", shape={shape}")?;
+        if flags.contains(Flags::DIRTY) {
+            write!(out, ", color=red")?;
+        } else if flags.contains(Flags::PENDING) {
+            write!(out, ", color=orange")?;
+        }
+        writeln!(out, "];")?;
+    }
+    for (dep, sub, version) in links {
+        writeln!(out, "    n{dep} -> n{sub} [label=\"v{version}\"];")?;
+    }
+    writeln!(out, "}}")
+}
+
+fn write_json(
+    out: &mut String,
+    nodes: &[NodeId],
+    links: &[(usize, usize, usize)],
+) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    write!(out, "{{\"nodes\":[")?;
+    for (id, node) in nodes.iter().enumerate() {
+        if id > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "{{\"id\":{id},\"kind\":\"{}\",\"label\":", node.kind())?;
+        match node.name() {
+            Some(name) => write_json_string(out, &name)?,
+            None => write!(out, "null")?,
+        }
+        write!(out, ",\"flags\":[")?;
+        for (i, name) in node.flags().names().enumerate() {
+            if i > 0 {
+                write!(out, ",")?;
+            }
+            write!(out, "\"{name}\"")?;
+        }
+        write!(out, "]}}")?;
+    }
+    write!(out, "],\"links\":[")?;
+    for (i, (dep, sub, version)) in links.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "{{\"dep\":{dep},\"sub\":{sub},\"version\":{version}}}")?;
+    }
+    write!(out, "]}}")
+}
+
+fn write_dot_escaped(out: &mut String, s: &str) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    for c in s.chars() {
+        match c {
+            '"' => write!(out, "\\\"")?,
+            '\\' => write!(out, "\\\\")?,
+            '\n' => write!(out, "\\n")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+fn write_json_string(out: &mut String, s: &str) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(out, "\\\"")?,
+            '\\' => write!(out, "\\\\")?,
+            '\n' => write!(out, "\\n")?,
+            '\r' => write!(out, "\\r")?,
+            '\t' => write!(out, "\\t")?,
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
@@ -0,0 +1,81 @@
+//! Creation sites of effects, effect scopes and computeds, for the `leak-detector` feature.
+
+use crate::inspect::{NodeId, walk};
+use crate::{Flags, NodeKind};
+use std::panic::Location;
+
+/// An effect, effect scope or computed still alive, returned by [`report_leaks`].
+///
+/// Displayed like `` `Effect` "autosave" created at src/main.rs:12:5 ``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Leak {
+    pub node: NodeId,
+    pub kind: NodeKind,
+    /// the name given by `.named()`
+    pub name: Option<String>,
+    /// where the node was created, by the caller of `new` or the alias like [`effect`](crate::effect)
+    pub created_at: &'static Location<'static>,
+}
+
+impl std::fmt::Display for Leak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`", self.kind)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name:?}")?;
+        }
+        write!(f, " created at {}", self.created_at)
+    }
+}
+
+/// List the effects, effect scopes and computeds of the current runtime that
+/// are not disposed yet, e.g. at the end of a test
+/// to find forgotten `.dispose()` calls:
+///
+/// ```
+/// use alien_signals::{Signal, Effect, report_leaks};
+///
+/// let count = Signal::new(0);
+/// let e = Effect::new(move || println!("{}", count.get()));
+///
+/// let leaks = report_leaks();
+/// assert_eq!(leaks.len(), 1);
+/// println!("{}", leaks[0]); // `Effect` created at src/main.rs:5:9
+///
+/// e.dispose();
+/// assert!(report_leaks().is_empty());
+/// ```
+///
+/// Effects and effect scopes created in another one are not listed while
+/// it's alive, as they're disposed together with it. Computeds are listed
+/// only while observed, i.e. read by an effect or a computed observed itself,
+/// as an unobserved one doesn't run until it's read again.
+///
+/// To check another [`Runtime`](crate::Runtime), call this in its `enter`.
+pub fn report_leaks() -> Vec<Leak> {
+    let mut leaks = Vec::new();
+    walk(|node| {
+        let Some(created_at) = node.0.created_at() else {
+            return;
+        };
+        let kind = node.kind();
+        let is_leak = match kind {
+            // not owned by another one
+            NodeKind::Effect | NodeKind::EffectScope => node.subscribers().is_empty(),
+            // observed
+            NodeKind::Computed => {
+                !node.subscribers().is_empty() || node.flags().contains(Flags::WATCHING)
+            }
+            _ => true,
+        };
+        if is_leak {
+            leaks.push(Leak {
+                node,
+                kind,
+                name: node.name(),
+                created_at,
+            });
+        }
+    });
+    leaks
+}
@@ -1,15 +1,44 @@
 // for doctest inclusion
 #![cfg_attr(all(doc, not(docsrs)), doc = include_str!("../README.md"))]
 
+mod error;
+mod future;
+pub mod inspect;
+#[cfg(feature = "leak-detector")]
+mod leak;
 mod node;
 mod primitive;
+mod resource;
+mod runtime;
+mod stats;
+#[cfg(feature = "stream")]
+mod stream;
 mod system;
+mod trace;
+mod watch;
+
+#[cfg(feature = "sync")]
+pub mod sync;
 
 use node::{ComputedContext, EffectContext, Node, NodeContext, NodeContextKind, SignalContext};
 use primitive::{SmallAny, Version};
 
+pub use error::Error;
+pub use future::{AsyncComputed, AsyncState, SpawnedFuture, async_computed, spawn_effect};
+#[cfg(feature = "leak-detector")]
+pub use leak::{Leak, report_leaks};
+pub use node::NodeKind;
 pub use primitive::Flags;
-pub use system::{end_batch, get_active_sub, get_batch_depth, set_active_sub, start_batch};
+pub use resource::Resource;
+pub use runtime::Runtime;
+pub use stats::{Stats, stats};
+#[cfg(feature = "stream")]
+pub use stream::SignalStream;
+pub use system::{
+    BatchGuard, FlushRequest, batch, end_batch, flush_effects, get_active_sub, get_batch_depth,
+    remove_scheduler, set_active_sub, set_scheduler, start_batch,
+};
+pub use watch::{WatchOptions, watch};
 
 #[inline]
 fn update(signal_or_computed: Node) -> bool {
@@ -26,10 +55,10 @@ fn notify(mut effect: Node<EffectContext>) {
     // just push directly to `ququed` and finally reverse newly-pushed part
     let chain_head_index = system::with_queued(|q| q.length());
     loop {
-        effect.remove_flags(Flags::WATCHING);
+        effect.remove_flags_unchecked(Flags::WATCHING);
         system::with_queued(|q| q.push(effect));
         match effect.subs().map(|s| s.sub()) {
-            Some(subs_sub) if (subs_sub.flags() & Flags::WATCHING).is_nonzero() => {
+            Some(subs_sub) if (subs_sub.flags_unchecked() & Flags::WATCHING).is_nonzero() => {
                 effect = subs_sub
                     .try_into()
                     .expect("BUG: `subs.sub` of an effect is not effect");
@@ -41,20 +70,30 @@ fn notify(mut effect: Node<EffectContext>) {
 }
 
 fn unwatched(node: Node) {
-    if (node.flags() & Flags::MUTABLE).is_zero() {
+    if (node.flags_unchecked() & Flags::MUTABLE).is_zero() {
         effect_scope_oper(node);
+        // nothing can re-link an unwatched effect or scope
+        system::release_node(node);
     } else if node.deps_tail().is_some() {
         node.set_deps_tail(None);
-        node.set_flags(Flags::MUTABLE | Flags::DIRTY);
+        node.set_flags_unchecked(Flags::MUTABLE | Flags::DIRTY);
         purge_deps(node);
     }
 }
 
 /// alias of [`Signal::new`]
-pub fn signal<T: Clone + PartialEq + 'static>(init: T) -> Signal<T> {
+#[track_caller]
+pub fn signal<T: PartialEq + 'static>(init: T) -> Signal<T> {
     Signal::new(init)
 }
 
+/// Handles are bound to the runtime of the thread that created them,
+/// so they are `!Send` and `!Sync`:
+///
+/// ```compile_fail
+/// let count = alien_signals::signal(0);
+/// std::thread::spawn(move || count.get());
+/// ```
 pub struct Signal<T>(Node<SignalContext>, std::marker::PhantomData<T>);
 // not requiring `T: Clone`
 impl<T> Clone for Signal<T> {
@@ -63,51 +102,164 @@ impl<T> Clone for Signal<T> {
     }
 }
 impl<T> Copy for Signal<T> {}
-impl<T: Clone + 'static> Signal<T> {
+impl<T: std::fmt::Debug + 'static> std::fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_handle(f, "Signal", self.0, NodeKind::Signal, |d| {
+            self.0.borrow_context(
+                |SignalContext {
+                     current_value,
+                     pending_value,
+                     ..
+                 }| {
+                    let latest = pending_value.as_ref().unwrap_or(current_value);
+                    // SAFETY: the type is guaranteed to be `T` by the constructor
+                    d.field("value", unsafe { latest.downcast_ref_unchecked::<T>() });
+                },
+            )
+        })
+    }
+}
+impl<T: 'static> Signal<T> {
+    #[track_caller]
     pub fn new(init: T) -> Self
     where
         T: PartialEq,
     {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("Signal::new");
         let node = Node::<SignalContext>::new(init);
         Self(node, std::marker::PhantomData)
     }
+    #[track_caller]
     pub fn new_with_eq_fn(init: T, eq_fn: impl Fn(&T, &T) -> bool + 'static) -> Self {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("Signal::new_with_eq_fn");
         let node = Node::<SignalContext>::new_with_eq_fn(init, eq_fn);
         Self(node, std::marker::PhantomData)
     }
 
+    /// Name this signal for debugging. The name is shown in `Debug`,
+    /// panic messages and [`inspect::export_graph`].
+    ///
+    /// ```
+    /// let total = alien_signals::signal(0).named("cart_total");
+    /// assert_eq!(
+    ///     format!("{total:?}"),
+    ///     r#"Signal { name: "cart_total", flags: Flags(MUTABLE), value: 0 }"#,
+    /// );
+    /// ```
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        assert_handle(self.0, NodeKind::Signal);
+        self.0.set_name(name.into());
+        self
+    }
+
+    /// ## Panics
+    ///
+    /// if this signal has been disposed
     #[inline]
-    pub fn get(&self) -> T {
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        assert_handle(self.0, NodeKind::Signal);
         signal_get_oper(self.0)
     }
+    /// non-panicking variant of [`Signal::get`]
+    #[inline]
+    pub fn try_get(&self) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        check_handle(self.0, NodeKind::Signal)?;
+        Ok(signal_get_oper(self.0))
+    }
 
+    /// Read the value without subscribing to this signal.
+    /// Equivalent to `untracked(|| signal.get())`.
+    ///
+    /// ## Panics
+    ///
+    /// if this signal has been disposed
     #[inline]
+    #[track_caller]
+    pub fn peek(&self) -> T
+    where
+        T: Clone,
+    {
+        assert_handle(self.0, NodeKind::Signal);
+        untracked(|| signal_get_oper(self.0))
+    }
+
+    /// Read the value by reference without cloning it, tracking this signal
+    /// just like [`Signal::get`].
+    ///
+    /// ## Panics
+    ///
+    /// - if this signal has been disposed
+    /// - if `f` sets this signal
+    #[inline]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        assert_handle(self.0, NodeKind::Signal);
+        signal_with_oper(self.0, f)
+    }
+
+    #[inline]
+    #[track_caller]
     pub fn set(&self, value: T) {
-        signal_set_oper(self.0, value);
+        assert_handle(self.0, NodeKind::Signal);
+        setting(|| signal_set_oper(self.0, value));
     }
 
     /// set with current value
+    #[track_caller]
     pub fn set_with(&self, f: impl FnOnce(&T) -> T) {
-        signal_set_with_oper(self.0, f);
+        assert_handle(self.0, NodeKind::Signal);
+        setting(|| signal_set_with_oper(self.0, f));
     }
 
     #[deprecated(since = "0.1.4", note = "use `.set_mut()` instead")]
     pub fn update(&self, f: impl FnOnce(&mut T)) {
         self.set_mut(f)
     }
+    /// Mutate the value in place, and notify subscribers as changed
+    /// without comparing to the previous value.
+    ///
+    /// ## Panics
+    ///
+    /// - if this signal has been disposed
+    /// - if `f` reads this signal
     #[inline]
+    #[track_caller]
     pub fn set_mut(&self, f: impl FnOnce(&mut T)) {
-        signal_set_mut_oper(self.0, f);
+        assert_handle(self.0, NodeKind::Signal);
+        setting(|| signal_set_mut_oper(self.0, f));
+    }
+
+    /// Unsubscribe all subscribers from this signal and free it.
+    /// Using this signal or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        dispose_oper(self.0.into(), NodeKind::Signal);
     }
 }
 
 /// alias of [`Computed::new`]
-pub fn computed<T: Clone + PartialEq + 'static>(
-    getter: impl Fn(Option<&T>) -> T + 'static,
-) -> Computed<T> {
+#[track_caller]
+pub fn computed<T: PartialEq + 'static>(getter: impl Fn(Option<&T>) -> T + 'static) -> Computed<T> {
     Computed::new(getter)
 }
 
+/// Handles are bound to the runtime of the thread that created them,
+/// so they are `!Send` and `!Sync`:
+///
+/// ```compile_fail
+/// let double = alien_signals::computed(|_| 2);
+/// std::thread::spawn(move || double.get());
+/// ```
 pub struct Computed<T>(Node<ComputedContext>, std::marker::PhantomData<T>);
 // not requiring `T: Clone`
 impl<T> Clone for Computed<T> {
@@ -116,188 +268,636 @@ impl<T> Clone for Computed<T> {
     }
 }
 impl<T> Copy for Computed<T> {}
-impl<T: Clone + 'static> Computed<T> {
+/// shows the cached value, without recomputing it
+impl<T: std::fmt::Debug + 'static> std::fmt::Debug for Computed<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_handle(f, "Computed", self.0, NodeKind::Computed, |d| {
+            self.0.borrow_context(|ComputedContext { value, .. }| {
+                if let Some(value) = value {
+                    // SAFETY: the type is guaranteed to be `T` by the constructor
+                    d.field("value", unsafe { value.downcast_ref_unchecked::<T>() });
+                }
+            })
+        })
+    }
+}
+impl<T: 'static> Computed<T> {
+    #[track_caller]
     pub fn new(getter: impl Fn(Option<&T>) -> T + 'static) -> Self
     where
         T: PartialEq,
     {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("Computed::new");
         let node = Node::<ComputedContext>::new(getter);
+        #[cfg(feature = "leak-detector")]
+        node.set_created_at(std::panic::Location::caller());
         Self(node, std::marker::PhantomData)
     }
+    #[track_caller]
     pub fn new_with_eq(
         getter: impl Fn(Option<&T>) -> T + 'static,
         eq_fn: impl Fn(&T, &T) -> bool + 'static,
     ) -> Self {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("Computed::new_with_eq");
         let node = Node::<ComputedContext>::new_with_eq_fn(getter, eq_fn);
+        #[cfg(feature = "leak-detector")]
+        node.set_created_at(std::panic::Location::caller());
         Self(node, std::marker::PhantomData)
     }
 
+    /// Name this computed for debugging. See [`Signal::named`].
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        assert_handle(self.0, NodeKind::Computed);
+        self.0.set_name(name.into());
+        self
+    }
+
+    /// ## Panics
+    ///
+    /// - if this computed has been disposed
+    /// - if this computed is read while computing itself, directly or through
+    ///   other computeds, with the cycle like `total -> tax -> total`
     #[inline]
-    pub fn get(&self) -> T {
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        assert_computed(self.0);
         computed_oper(self.0)
     }
+    /// non-panicking variant of [`Computed::get`], returning [`Error::Cycle`]
+    /// instead of panicking when this read closes a cycle
+    #[inline]
+    pub fn try_get(&self) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        check_computed(self.0)?;
+        Ok(computed_oper(self.0))
+    }
+
+    /// Read the value without subscribing to this computed.
+    /// Equivalent to `untracked(|| computed.get())`.
+    ///
+    /// ## Panics
+    ///
+    /// - if this computed has been disposed
+    /// - if this computed is read while computing itself
+    #[inline]
+    #[track_caller]
+    pub fn peek(&self) -> T
+    where
+        T: Clone,
+    {
+        assert_computed(self.0);
+        untracked(|| computed_oper(self.0))
+    }
+
+    /// Read the value by reference without cloning it, tracking this computed
+    /// just like [`Computed::get`].
+    ///
+    /// ## Panics
+    ///
+    /// - if this computed has been disposed
+    /// - if this computed is read while computing itself
+    /// - if `f` causes this computed to be recomputed
+    #[inline]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        assert_computed(self.0);
+        computed_with_oper(self.0, f)
+    }
+
+    /// Unsubscribe this computed from its deps and subscribers, and free it.
+    /// Using this computed or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        dispose_oper(self.0.into(), NodeKind::Computed);
+    }
 }
 
 /// alias of [`Effect::new`]
+#[track_caller]
 pub fn effect(f: impl Fn() + 'static) -> Effect {
     Effect::new(f)
 }
 
 pub struct Effect {
-    dispose: Box<dyn FnOnce()>,
+    node: Node<EffectContext>,
+}
+impl std::fmt::Debug for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_handle(f, "Effect", self.node, NodeKind::Effect, |_| ())
+    }
 }
 impl Effect {
+    #[track_caller]
     pub fn new(f: impl Fn() + 'static) -> Self {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("Effect::new");
+        let _hold = system::hold();
         let e = Node::<EffectContext>::new(f);
-        let prev_sub = system::set_active_sub(Some(e.into()));
-        if let Some(prev_sub) = prev_sub {
+        #[cfg(feature = "leak-detector")]
+        e.set_created_at(std::panic::Location::caller());
+        let running = Running::start(e.into());
+        if let Some(prev_sub) = running.prev_sub {
             system::link(e.into(), prev_sub, Version::new());
         }
+        stats::count_effect_run();
         e.with_context(|EffectContext { run }| run());
-        system::set_active_sub(prev_sub);
-        e.remove_flags(Flags::RECURSED_CHECK);
-        Self {
-            dispose: Box::new(move || effect_oper(e)),
-        }
+        running.finish();
+        Self { node: e }
+    }
+
+    /// Create an effect passing the return value of the previous run to `f`,
+    /// like the getter of [`Computed`]. The first run gets `init`.
+    ///
+    /// ```
+    /// use alien_signals::{signal, Effect};
+    ///
+    /// let items = signal(vec![1, 2]);
+    /// Effect::new_with_state(0, move |prev_len| {
+    ///     let len = items.with(|items| items.len());
+    ///     println!("{prev_len} -> {len}");
+    ///     len
+    /// }); // 0 -> 2
+    ///
+    /// items.set_mut(|items| items.push(3)); // 2 -> 3
+    /// ```
+    ///
+    /// If `f` panics, the state moved into it is lost with it: the effect is
+    /// still run again when its deps change, but that run panics as the state
+    /// is lost, unless `f` catches its own panics to always return a state.
+    #[track_caller]
+    pub fn new_with_state<S: 'static>(init: S, f: impl Fn(S) -> S + 'static) -> Self {
+        let state = std::cell::Cell::new(Some(init));
+        Self::new(move || {
+            let prev = state
+                .take()
+                .expect("the state of this effect is lost by a panic in its previous run");
+            state.set(Some(f(prev)));
+        })
+    }
+
+    /// Name this effect for debugging. See [`Signal::named`].
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        assert_handle(self.node, NodeKind::Effect);
+        self.node.set_name(name.into());
+        self
+    }
+
+    /// Which signal set made this effect run the last time, and through which
+    /// computeds, for debugging. `None` for the first run, and always `None`
+    /// without the `causality` feature, where causes are not recorded: see
+    /// [`inspect::RECORDS_CAUSES`].
+    ///
+    /// When several signals are set in a batch, the first one reaching this
+    /// effect is the cause, even if the others changed what it read.
+    ///
+    /// ```
+    /// use alien_signals::{Signal, Computed, Effect};
+    /// use alien_signals::inspect::AsNodeId;
+    ///
+    /// let price = Signal::new(10).named("price");
+    /// let total = Computed::new(move |_| price.get() * 2).named("total");
+    /// let e = Effect::new(move || println!("{}", total.get()));
+    /// assert_eq!(e.last_cause(), None);
+    ///
+    /// price.set(20);
+    /// if let Some(cause) = e.last_cause() {
+    ///     assert_eq!(cause.signal, price.node_id());
+    ///     assert_eq!(cause.via, [total.node_id()]);
+    ///     println!("{cause}"); // "price" set at src/main.rs:9:7 -> "total"
+    /// }
+    /// assert_eq!(e.last_cause().is_some(), alien_signals::inspect::RECORDS_CAUSES);
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// if this effect has been disposed
+    #[track_caller]
+    pub fn last_cause(&self) -> Option<inspect::Cause> {
+        assert_handle(self.node, NodeKind::Effect);
+        #[cfg(feature = "causality")]
+        return self.node.last_cause();
+        #[cfg(not(feature = "causality"))]
+        None
     }
 
+    #[track_caller]
     pub fn dispose(self) {
-        (self.dispose)();
+        dispose_oper(self.node.into(), NodeKind::Effect);
+    }
+
+    /// Turn into an owning handle that disposes this effect on drop.
+    pub fn into_handle(self) -> EffectHandle {
+        EffectHandle {
+            node: self.node.into(),
+            kind: NodeKind::Effect,
+        }
     }
 }
 
 /// alias of [`EffectScope::new`]
+#[track_caller]
 pub fn effect_scope(f: impl FnOnce() + 'static) -> EffectScope {
     EffectScope::new(f)
 }
 
 pub struct EffectScope {
-    dispose: Box<dyn FnOnce()>,
+    node: Node,
+}
+impl std::fmt::Debug for EffectScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_handle(f, "EffectScope", self.node, NodeKind::EffectScope, |_| ())
+    }
 }
 impl EffectScope {
+    #[track_caller]
     pub fn new(f: impl FnOnce() + 'static) -> Self {
+        #[cfg(feature = "sync")]
+        sync::assert_not_shared("EffectScope::new");
+        let _hold = system::hold();
         let e = Node::<NodeContext>::new(Flags::NONE);
-        let prev_sub = system::set_active_sub(Some(e));
-        if let Some(prev_sub) = prev_sub {
+        #[cfg(feature = "leak-detector")]
+        e.set_created_at(std::panic::Location::caller());
+        let running = Running::start(e);
+        if let Some(prev_sub) = running.prev_sub {
             system::link(e, prev_sub, Version::new());
         }
         f();
-        system::set_active_sub(prev_sub);
-        Self {
-            dispose: Box::new(move || effect_scope_oper(e)),
+        running.finish();
+        Self { node: e }
+    }
+
+    /// Name this effect scope for debugging. See [`Signal::named`].
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        assert_handle(self.node, NodeKind::EffectScope);
+        self.node.set_name(name.into());
+        self
+    }
+
+    #[track_caller]
+    pub fn dispose(self) {
+        dispose_oper(self.node, NodeKind::EffectScope);
+    }
+
+    /// Turn into an owning handle that disposes this scope, and
+    /// the effects created in it, on drop.
+    pub fn into_handle(self) -> EffectHandle {
+        EffectHandle {
+            node: self.node,
+            kind: NodeKind::EffectScope,
         }
     }
+}
 
+/// Owning handle of an [`Effect`] or [`EffectScope`], disposing it on drop.
+///
+/// ```
+/// use alien_signals::{signal, Effect};
+///
+/// let count = signal(0);
+/// {
+///     let _handle = Effect::new(move || println!("{}", count.get())).into_handle();
+///     count.set(1); // prints 1
+/// }
+/// count.set(2); // prints nothing
+///
+/// // `detach` keeps the effect alive like a bare `Effect`
+/// Effect::new(move || println!("{}", count.get())).into_handle().detach();
+/// ```
+#[must_use = "the effect is disposed immediately if the handle is not bound"]
+pub struct EffectHandle {
+    node: Node,
+    kind: NodeKind,
+}
+impl EffectHandle {
     pub fn dispose(self) {
-        (self.dispose)();
+        drop(self);
+    }
+
+    /// Give up the ownership, keeping the effect alive until it's disposed
+    /// in another way (e.g. by its outer effect or scope).
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+impl std::fmt::Debug for EffectHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_handle(f, "EffectHandle", self.node, self.kind, |d| {
+            d.field("kind", &self.kind);
+        })
+    }
+}
+impl Drop for EffectHandle {
+    fn drop(&mut self) {
+        // never panic in drop: the effect may be already disposed by its owner,
+        // and the handle may be dropped out of its runtime (e.g. on thread exit)
+        if runtime::is_accessible() && check_handle(self.node, self.kind).is_ok() {
+            dispose_oper(self.node, self.kind);
+        }
     }
 }
 
+#[track_caller]
 pub fn trigger(f: impl FnOnce() + 'static) {
-    let sub = Node::<NodeContext>::new(Flags::WATCHING);
-    let prev_sub = system::set_active_sub(Some(sub));
+    setting(|| trigger_oper(f));
+}
+
+fn trigger_oper(f: impl FnOnce() + 'static) {
+    let _hold = system::hold();
+    let sub = Node::<NodeContext>::new_trigger();
+    let running = Running::start(sub);
     f();
-    system::set_active_sub(prev_sub);
+    running.finish();
     let mut link = sub.deps();
     while let Some(some_link) = link {
         let dep = some_link.dep();
         link = system::unlink(some_link, sub);
         if let Some(subs) = dep.subs() {
-            sub.set_flags(Flags::NONE);
+            sub.set_flags_unchecked(Flags::NONE);
             system::propagate(subs);
             system::shallow_propagate(subs);
         }
     }
+    system::release_node(sub);
     if system::get_batch_depth() == 0 {
-        flush();
+        system::schedule_flush();
+    }
+}
+
+/// Run `f` without tracking signals and computeds read in it.
+/// The active subscriber is restored after `f`, even on panic.
+///
+/// ```
+/// use alien_signals::{signal, computed, untracked};
+///
+/// let a = signal(1);
+/// let b = signal(10);
+/// let sum = computed(move |_| a.get() + untracked(|| b.get()));
+/// assert_eq!(sum.get(), 11);
+///
+/// b.set(20);
+/// assert_eq!(sum.get(), 11); // not subscribing to `b`
+/// ```
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Node>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            system::set_active_sub(self.0);
+        }
+    }
+
+    let _restore = Restore(system::set_active_sub(None));
+    f()
+}
+
+/// Register `cleanup` to the currently running effect, computed or effect scope.
+///
+/// Cleanups run before the next re-run of it and when it's disposed,
+/// in reverse order of registration. Out of any of them (including in
+/// [`untracked`]), `cleanup` is just dropped without running.
+///
+/// ```
+/// use alien_signals::{signal, effect, on_cleanup};
+///
+/// let id = signal(1);
+/// let e = effect(move || {
+///     let id = id.get();
+///     println!("subscribe {id}");
+///     on_cleanup(move || println!("unsubscribe {id}"));
+/// }); // subscribe 1
+///
+/// id.set(2); // unsubscribe 1, subscribe 2
+/// e.dispose(); // unsubscribe 2
+/// ```
+#[track_caller]
+pub fn on_cleanup(cleanup: impl FnOnce() + 'static) {
+    #[cfg(feature = "sync")]
+    sync::assert_not_shared("on_cleanup");
+    if let Some(sub) = system::get_active_sub() {
+        sub.push_cleanup(Box::new(cleanup));
+    }
+}
+
+fn run_cleanups(node: Node) {
+    let cleanups = node.take_cleanups();
+    if !cleanups.is_empty() {
+        untracked(|| {
+            cleanups
+                .into_iter()
+                .rev()
+                .for_each(|(_, cleanup)| cleanup())
+        });
     }
 }
 
 #[inline]
 fn update_computed(c: Node<ComputedContext>) -> bool {
+    let span = trace::span!("update_computed", c, changed);
+    stats::count_recomputation();
+    run_cleanups(c.into());
     system::increment_cycle();
     c.set_deps_tail(None);
-    c.set_flags(Flags::MUTABLE | Flags::RECURSED_CHECK);
-    let prev_sub = system::set_active_sub(Some(c.into()));
+    c.set_flags_unchecked(Flags::MUTABLE | Flags::RECURSED_CHECK);
+    let mut running = Running::start(c.into());
+    let computing = system::start_computing(c.into());
 
-    // SAFETY: the closure does not internally call `.with_context` or `.with_context_mut` on `c`
-    let is_changed = unsafe {
-        c.with_context_mut(|ComputedContext { value, get, eq }| {
-            let new_value = get(value.as_ref());
-            let is_changed = match value {
-                None => true, // initial update
-                Some(old_value) => !eq(old_value, &new_value),
-            };
-            *value = Some(new_value);
-            is_changed
-        })
-    };
+    let is_changed = c.borrow_context_mut(|ComputedContext { value, get, eq }| {
+        if value.is_none() {
+            // computed again by the next read, having no value to return
+            running.on_unwind(Flags::DIRTY);
+        }
+        let new_value = get(value.as_ref());
+        let is_changed = match value {
+            None => true, // initial update
+            Some(old_value) => !eq(old_value, &new_value),
+        };
+        *value = Some(new_value);
+        is_changed
+    });
 
-    system::set_active_sub(prev_sub);
-    c.remove_flags(Flags::RECURSED_CHECK);
+    drop(computing);
+    running.finish();
     purge_deps(c.into());
 
+    trace::record!(span, changed, is_changed);
     is_changed
 }
 
 #[inline]
 fn update_signal(s: Node<SignalContext>) -> bool {
-    s.set_flags(Flags::MUTABLE);
-    // SAFETY: the closure does not internally call `.with_context` or `.with_context_mut` on `s`
-    unsafe {
-        s.with_context_mut(
-            |SignalContext {
-                 current_value,
-                 pending_value,
-                 eq,
-             }| {
-                let is_changed = !eq(current_value, pending_value);
-                *current_value = pending_value.clone();
+    let span = trace::span!("update_signal", s, changed);
+    s.set_flags_unchecked(Flags::MUTABLE);
+    let is_changed = s.borrow_context_mut(
+        |SignalContext {
+             current_value,
+             pending_value,
+             eq,
+         }| match pending_value.take() {
+            Some(pending_value) => {
+                let is_changed = !eq(current_value, &pending_value);
+                *current_value = pending_value;
                 is_changed
-            },
-        )
+            }
+            // mutated in place by `set_mut`
+            None => true,
+        },
+    );
+    trace::record!(span, changed, is_changed);
+    is_changed
+}
+
+/// Run `f` setting signals, recording the caller as where they're set
+/// in the causes of the effects notified
+#[track_caller]
+#[inline]
+fn setting<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "causality")]
+    let _restore = {
+        struct Restore(Option<&'static std::panic::Location<'static>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                if runtime::is_accessible() {
+                    runtime::with_system_mut(|sys| sys.setting_at = self.0);
+                }
+            }
+        }
+        let caller = std::panic::Location::caller();
+        Restore(runtime::with_system_mut(|sys| {
+            sys.setting_at.replace(caller)
+        }))
+    };
+    f()
+}
+
+/// Makes `sub` the active subscriber to run user's code in it. If the code panics,
+/// the drop restores the previous one and removes `RECURSED_CHECK` of `sub`,
+/// so that reads after the panic is caught are not linked to `sub`, and
+/// `sub` is run again when its deps change.
+struct Running {
+    sub: Node,
+    prev_sub: Option<Node>,
+    unwind_flags: Flags,
+}
+impl Running {
+    #[inline]
+    fn start(sub: Node) -> Self {
+        Self {
+            sub,
+            prev_sub: system::set_active_sub(Some(sub)),
+            unwind_flags: Flags::NONE,
+        }
+    }
+    /// Set `flags` on `sub` too if the code panics
+    #[inline]
+    fn on_unwind(&mut self, flags: Flags) {
+        self.unwind_flags = flags;
+    }
+    #[inline]
+    fn finish(self) {
+        let this = std::mem::ManuallyDrop::new(self);
+        system::set_active_sub(this.prev_sub);
+        this.sub.remove_flags_unchecked(Flags::RECURSED_CHECK);
+    }
+}
+impl Drop for Running {
+    fn drop(&mut self) {
+        if runtime::is_accessible() {
+            system::set_active_sub(self.prev_sub);
+            let flags = self.sub.flags_unchecked();
+            self.sub
+                .set_flags_unchecked((flags & !Flags::RECURSED_CHECK) | self.unwind_flags);
+        }
     }
 }
 
 fn run(e: Node<EffectContext>) {
-    let flags = e.flags();
+    let span = trace::span!("run_effect", e, skipped);
+    let flags = e.flags_unchecked();
     if (flags & Flags::DIRTY).is_nonzero()
         || ((flags & Flags::PENDING).is_nonzero()
             && system::check_dirty(
-                e.deps().expect("BUG: effect node has no `deps` in `run`"),
+                e.deps().unwrap_or_else(|| {
+                    panic!(
+                        "BUG: {} has no `deps` in `run`",
+                        e.describe(NodeKind::Effect)
+                    )
+                }),
                 e.into(),
             ))
     {
+        trace::record!(span, skipped, false);
+        #[cfg(feature = "causality")]
+        e.settle_cause(true);
+        run_cleanups(e.into());
         system::increment_cycle();
         e.set_deps_tail(None);
-        e.set_flags(Flags::WATCHING | Flags::RECURSED_CHECK);
-        let prev_sub = system::set_active_sub(Some(e.into()));
+        e.set_flags_unchecked(Flags::WATCHING | Flags::RECURSED_CHECK);
+        let running = Running::start(e.into());
+        stats::count_effect_run();
         e.with_context(|EffectContext { run }| run());
-        system::set_active_sub(prev_sub);
-        e.remove_flags(Flags::RECURSED_CHECK);
+        running.finish();
         purge_deps(e.into());
     } else {
-        e.set_flags(Flags::WATCHING);
+        trace::record!(span, skipped, true);
+        #[cfg(feature = "causality")]
+        e.settle_cause(false);
+        e.set_flags_unchecked(Flags::WATCHING);
     }
 }
 
 fn flush() {
+    let span = trace::span!("flush"; effects);
+    stats::count_flush();
+    let _hold = system::hold();
+    let mut count = 0_usize;
     while let Some(effect) = system::with_queued(|q| q.pop()) {
         run(effect);
+        count += 1;
     }
+    trace::record!(span, effects, count);
 }
 
 fn computed_oper<T: Clone + 'static>(this: Node<ComputedContext>) -> T {
-    let flags = this.flags();
+    let _hold = system::hold();
+    track_computed(this);
+    // SAFETY: `T::clone` is trusted not to reach `this` again, so this read on
+    // every `get` is not counted as a borrow (unlike `with` closures and getters)
+    unsafe { this.with_context(|context| computed_value::<T>(this, context).clone()) }
+}
+
+fn computed_with_oper<T: 'static, R>(this: Node<ComputedContext>, f: impl FnOnce(&T) -> R) -> R {
+    let _hold = system::hold();
+    track_computed(this);
+    this.borrow_context(|context| f(computed_value(this, context)))
+}
+
+/// Update `this` if needed and link it to the active subscriber, for a read under a `Hold`
+fn track_computed(this: Node<ComputedContext>) {
+    let flags = this.flags_unchecked();
     if (flags & Flags::DIRTY).is_nonzero()
         || ((flags & Flags::PENDING).is_nonzero() && {
             if system::check_dirty(
-                this.deps().expect("BUG: `deps` is None in `computed_oper`"),
+                this.deps().unwrap_or_else(|| {
+                    panic!(
+                        "BUG: `deps` of {} is None in `computed_oper`",
+                        this.describe(NodeKind::Computed)
+                    )
+                }),
                 this.into(),
             ) {
                 true
             } else {
-                this.set_flags(flags & !Flags::PENDING);
+                trace::event!("deps unchanged, skipped recomputation", this);
+                this.set_flags_unchecked(flags & !Flags::PENDING);
                 false
             }
         })
@@ -308,59 +908,63 @@ fn computed_oper<T: Clone + 'static>(this: Node<ComputedContext>) -> T {
             }
         }
     } else if flags.is_zero() {
-        this.set_flags(Flags::MUTABLE | Flags::RECURSED_CHECK);
-        let prev_sub = system::set_active_sub(Some(this.into()));
-        // SAFETY: the closure does not internally call `.with_context` or `.with_context_mut` on `this`
-        unsafe {
-            this.with_context_mut(|ComputedContext { value, get, .. }| {
-                let new_value = get(value.as_ref());
-                *value = Some(new_value);
-            });
-        }
-        system::set_active_sub(prev_sub);
-        this.remove_flags(Flags::RECURSED_CHECK);
+        this.set_flags_unchecked(Flags::MUTABLE | Flags::RECURSED_CHECK);
+        stats::count_recomputation();
+        let mut running = Running::start(this.into());
+        // computed again by the next read, having no value to return
+        running.on_unwind(Flags::DIRTY);
+        let computing = system::start_computing(this.into());
+        this.borrow_context_mut(|ComputedContext { value, get, .. }| {
+            let new_value = get(value.as_ref());
+            *value = Some(new_value);
+        });
+        drop(computing);
+        running.finish();
     }
 
     if let Some(sub) = system::get_active_sub() {
         system::link(this.into(), sub, system::get_cycle());
     }
+}
 
-    // SAFETY:
-    //
-    // - `with_context`: the closure does not internally call `.with_context_mut` on `this`.
-    // - `downcast_ref_unchecked`: the type is guaranteed to be `T` by the constructor.
-    unsafe {
-        this.with_context(|ComputedContext { value, .. }| {
-            value
-                .as_ref()
-                .expect("BUG: computed value is None")
-                .downcast_ref_unchecked::<T>()
-                .clone()
-        })
-    }
+fn computed_value<T: 'static>(this: Node<ComputedContext>, context: &ComputedContext) -> &T {
+    let value = context.value.as_ref().unwrap_or_else(|| {
+        panic!(
+            "BUG: value of {} is None",
+            this.describe(NodeKind::Computed)
+        )
+    });
+    // SAFETY: the type is guaranteed to be `T` by the constructor
+    unsafe { value.downcast_ref_unchecked::<T>() }
 }
 
 fn _signal_set_oper_core<T: 'static>(this: Node<SignalContext>, value: T) {
+    let _hold = system::hold();
     let value = SmallAny::new(value);
-    // SAFETY: the closure does not internally call `.with_context` or `.with_context_mut` on `this`
-    let is_changed = unsafe {
-        this.with_context_mut(
-            |SignalContext {
-                 pending_value, eq, ..
-             }| {
-                let is_changed = !eq(pending_value, &value);
-                *pending_value = value;
-                is_changed
-            },
-        )
-    };
-    if is_changed {
-        this.set_flags(Flags::MUTABLE | Flags::DIRTY);
-        if let Some(subs) = this.subs() {
-            system::propagate(subs);
-            if system::get_batch_depth() == 0 {
-                flush();
+    let is_changed = this.borrow_context_mut(
+        |SignalContext {
+             current_value,
+             pending_value,
+             eq,
+         }| {
+            let is_changed = !eq(pending_value.as_ref().unwrap_or(current_value), &value);
+            if is_changed {
+                *pending_value = Some(value);
             }
+            is_changed
+        },
+    );
+    if is_changed {
+        signal_notify(this);
+    }
+}
+
+fn signal_notify(this: Node<SignalContext>) {
+    this.set_flags_unchecked(Flags::MUTABLE | Flags::DIRTY);
+    if let Some(subs) = this.subs() {
+        system::propagate(subs);
+        if system::get_batch_depth() == 0 {
+            system::schedule_flush();
         }
     }
 }
@@ -370,36 +974,68 @@ fn signal_set_oper<T: 'static>(this: Node<SignalContext>, value: T) {
 }
 
 fn signal_set_with_oper<T: 'static>(this: Node<SignalContext>, set_with: impl FnOnce(&T) -> T) {
-    // SAFETY:
-    //
-    // - `with_context`: the closure does not internally call `.with_context_mut` on `this`.
-    // - `downcast_ref_unchecked`: the type is guaranteed to be `T` by the constructor.
-    let value = unsafe {
-        this.with_context(|SignalContext { current_value, .. }| {
-            let current_value = current_value.downcast_ref_unchecked::<T>();
-            set_with(current_value)
-        })
-    };
+    let _hold = system::hold();
+    // `pending_value` is the latest one if any, as it's moved into
+    // `current_value` only when this signal is read
+    let value = this.borrow_context(
+        |SignalContext {
+             current_value,
+             pending_value,
+             ..
+         }| {
+            let latest = pending_value.as_ref().unwrap_or(current_value);
+            // SAFETY: the type is guaranteed to be `T` by the constructor
+            set_with(unsafe { latest.downcast_ref_unchecked::<T>() })
+        },
+    );
     _signal_set_oper_core(this, value);
 }
 
-fn signal_set_mut_oper<T: Clone + 'static>(this: Node<SignalContext>, update: impl FnOnce(&mut T)) {
-    // SAFETY:
-    //
-    // - `with_context`: the closure does not internally call `.with_context_mut` on `this`.
-    // - `downcast_ref_unchecked`: the type is guaranteed to be `T` by the constructor.
-    let value = unsafe {
+fn signal_set_mut_oper<T: 'static>(this: Node<SignalContext>, update: impl FnOnce(&mut T)) {
+    let _hold = system::hold();
+    this.borrow_context_mut(
+        |SignalContext {
+             current_value,
+             pending_value,
+             ..
+         }| {
+            // move the pending value first to mutate the latest one in place,
+            // then `update_signal` reports it as changed without comparing
+            if let Some(pending_value) = pending_value.take() {
+                *current_value = pending_value;
+            }
+            // SAFETY: the type is guaranteed to be `T` by the constructor
+            update(unsafe { current_value.downcast_mut_unchecked::<T>() });
+        },
+    );
+    signal_notify(this);
+}
+
+fn signal_get_oper<T: Clone + 'static>(this: Node<SignalContext>) -> T {
+    let _hold = system::hold();
+    track_signal(this);
+    // SAFETY: same as `computed_oper`
+    unsafe {
         this.with_context(|SignalContext { current_value, .. }| {
-            let mut value = current_value.downcast_ref_unchecked::<T>().clone();
-            update(&mut value);
-            value
+            // SAFETY: the type is guaranteed to be `T` by the constructor
+            current_value.downcast_ref_unchecked::<T>().clone()
         })
-    };
-    _signal_set_oper_core(this, value);
+    }
 }
 
-fn signal_get_oper<T: Clone + 'static>(this: Node<SignalContext>) -> T {
-    if (this.flags() & Flags::DIRTY).is_nonzero() {
+fn signal_with_oper<T: 'static, R>(this: Node<SignalContext>, f: impl FnOnce(&T) -> R) -> R {
+    let _hold = system::hold();
+    track_signal(this);
+    this.borrow_context(|SignalContext { current_value, .. }| {
+        // SAFETY: the type is guaranteed to be `T` by the constructor
+        f(unsafe { current_value.downcast_ref_unchecked::<T>() })
+    })
+}
+
+/// Apply the pending value of `this` if any and link it to the active subscriber,
+/// for a read under a `Hold`
+fn track_signal(this: Node<SignalContext>) {
+    if (this.flags_unchecked() & Flags::DIRTY).is_nonzero() {
         if update_signal(this) {
             if let Some(subs) = this.subs() {
                 system::shallow_propagate(subs);
@@ -409,31 +1045,100 @@ fn signal_get_oper<T: Clone + 'static>(this: Node<SignalContext>) -> T {
 
     let mut sub = system::get_active_sub();
     while let Some(some_sub) = sub {
-        if (some_sub.flags() & (Flags::MUTABLE | Flags::WATCHING)).is_nonzero() {
+        if (some_sub.flags_unchecked() & (Flags::MUTABLE | Flags::WATCHING)).is_nonzero() {
             system::link(this.into(), some_sub, system::get_cycle());
             break;
         }
         sub = some_sub.subs().map(|it| it.sub());
     }
+}
 
-    // SAFETY:
-    //
-    // - `with_context`: the closure does not internally call `.with_context_mut` on `this`
-    // - `downcast_ref_unchecked`: the type is guaranteed to be `T` by the constructor.
-    unsafe {
-        this.with_context(|SignalContext { current_value, .. }| {
-            current_value.downcast_ref_unchecked::<T>().clone()
-        })
+/// Dispose `this` for good. The slot is freed when nothing is running.
+/// Does nothing if `this` is already disposed (e.g. an inner effect
+/// automatically disposed by the re-run of its outer effect).
+#[track_caller]
+fn dispose_oper(this: Node, kind: NodeKind) {
+    match check_handle(this, kind) {
+        Ok(()) => (),
+        Err(Error::Disposed(_)) => return,
+        Err(e) => panic!("{e}"),
+    }
+    let _hold = system::hold();
+    effect_scope_oper(this);
+    // signals and computeds may have multiple subs
+    while let Some(sub) = this.subs() {
+        system::unlink(sub, sub.sub());
+    }
+    system::release_node(this);
+}
+
+/// `Debug` of a handle showing its name and flags, and the fields
+/// added by `fields` if the handle is usable
+fn debug_handle<C>(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    node: Node<C>,
+    kind: NodeKind,
+    fields: impl FnOnce(&mut std::fmt::DebugStruct<'_, '_>),
+) -> std::fmt::Result {
+    let mut d = f.debug_struct(name);
+    match check_handle(node, kind) {
+        Ok(()) => {
+            if let Some(name) = node.name() {
+                d.field("name", &name);
+            }
+            d.field("flags", &node.flags_unchecked());
+            fields(&mut d);
+        }
+        Err(e) => {
+            d.field("error", &e);
+        }
     }
+    d.finish()
 }
 
-fn effect_oper(this: Node<EffectContext>) {
-    effect_scope_oper(this.into());
+#[inline]
+fn check_handle<C>(node: Node<C>, kind: NodeKind) -> Result<(), Error> {
+    if !node.is_in_current_runtime() {
+        Err(Error::WrongRuntime(kind))
+    } else if node.is_disposed() {
+        Err(Error::Disposed(kind))
+    } else {
+        Ok(())
+    }
+}
+#[inline]
+#[track_caller]
+fn assert_handle<C>(node: Node<C>, kind: NodeKind) {
+    if let Err(e) = check_handle(node, kind) {
+        panic!("{e}")
+    }
+}
+
+/// `check_handle` of a computed, also checking that it's not read while computing itself
+#[inline]
+fn check_computed(node: Node<ComputedContext>) -> Result<(), Error> {
+    check_handle(node, NodeKind::Computed)?;
+    // set while computing, or left by a panic in the getter
+    if (node.flags_unchecked() & Flags::RECURSED_CHECK).is_nonzero() {
+        if let Some(cycle) = system::cycle_of(node.into()) {
+            let labels = cycle.into_iter().map(|node| trace::Label(node).to_string());
+            return Err(Error::Cycle(labels.collect()));
+        }
+    }
+    Ok(())
+}
+#[inline]
+#[track_caller]
+fn assert_computed(node: Node<ComputedContext>) {
+    if let Err(e) = check_computed(node) {
+        panic!("{e}")
+    }
 }
 
 fn effect_scope_oper(this: Node) {
     this.set_deps_tail(None);
-    this.set_flags(Flags::NONE);
+    this.set_flags_unchecked(Flags::NONE);
     purge_deps(this);
     if let Some(sub) = this.subs() {
         system::unlink(sub, sub.sub());
@@ -1,10 +1,15 @@
-use crate::primitive::{ChunkedArena, Flags, SmallAny, SyncUnsafeCell, Version};
+use crate::primitive::{ChunkedArena, Flags, SmallAny, Version};
+use crate::runtime::{self, with_arena_mut};
+use std::borrow::Cow;
 
 pub enum NodeContext {
     Signal(SignalContext),
     Computed(ComputedContext),
     Effect(EffectContext),
+    /// of an effect scope
     None,
+    /// of the temporary subscriber of `trigger`
+    Trigger,
 }
 
 #[derive(Clone, Copy)]
@@ -13,6 +18,7 @@ pub(crate) enum NodeContextKind {
     Computed,
     Effect,
     None,
+    Trigger,
 }
 impl NodeContext {
     pub(crate) fn kind(&self) -> NodeContextKind {
@@ -21,13 +27,15 @@ impl NodeContext {
             NodeContext::Computed(_) => NodeContextKind::Computed,
             NodeContext::Effect(_) => NodeContextKind::Effect,
             NodeContext::None => NodeContextKind::None,
+            NodeContext::Trigger => NodeContextKind::Trigger,
         }
     }
 }
 
 pub struct SignalContext {
     pub(crate) current_value: SmallAny,
-    pub(crate) pending_value: SmallAny,
+    /// set by a changing set, and moved into `current_value` when read
+    pub(crate) pending_value: Option<SmallAny>,
     pub(crate) eq: Box<dyn Fn(&SmallAny, &SmallAny) -> bool>,
 }
 
@@ -50,34 +58,110 @@ struct LinkFields {
     prev_dep: Option<Link>,
     next_dep: Option<Link>,
 }
-const _: () = assert!(std::mem::size_of::<LinkFields>() == 7 * std::mem::size_of::<usize>());
+// no padding
+const _: () = assert!(
+    std::mem::size_of::<LinkFields>()
+        == std::mem::size_of::<Version>()
+            + 2 * std::mem::size_of::<Node>()
+            + 4 * std::mem::size_of::<Option<Link>>()
+);
 
 struct NodeFields {
     flags: Flags,
+    /// set when the node is disposed, and reset when the slot is reused
+    released: bool,
+    /// number of the shared borrows of `context`, or `BORROWED_MUT`
+    borrow: u16,
+    /// incremented every time the node is freed, to detect stale handles
+    generation: u32,
     deps: Option<Link>,
     deps_tail: Option<Link>,
     subs: Option<Link>,
     subs_tail: Option<Link>,
-    context: Box<NodeContext>,
+    /// `None` after the node is freed
+    context: Option<Box<NodeContext>>,
+    /// allocated only when needed
+    extra: Option<Box<NodeExtra>>,
 }
-const _: () = assert!(std::mem::size_of::<NodeFields>() == 6 * std::mem::size_of::<usize>());
+const _: () = assert!(std::mem::size_of::<NodeFields>() == 7 * std::mem::size_of::<usize>());
+
+/// Rarely used data of a node, kept out of `context` so that it can be
+/// accessed while the context is borrowed (e.g. in the getter of a computed)
+#[derive(Default)]
+struct NodeExtra {
+    /// registered by `on_cleanup`
+    cleanups: Vec<(CleanupKey, Box<dyn FnOnce()>)>,
+    next_cleanup_key: u32,
+    /// set by `.named()`, for debugging
+    name: Option<Cow<'static, str>>,
+    /// of an effect, recorded by `propagate` until it runs
+    #[cfg(feature = "causality")]
+    pending_cause: Option<Box<crate::inspect::Cause>>,
+    /// of an effect, of its last run
+    #[cfg(feature = "causality")]
+    last_cause: Option<Box<crate::inspect::Cause>>,
+    /// of an effect, effect scope or computed, for `report_leaks`
+    #[cfg(feature = "leak-detector")]
+    created_at: Option<&'static std::panic::Location<'static>>,
+}
+
+const BORROWED_MUT: u16 = u16::MAX;
 
-struct Arena {
-    link: ChunkedArena<LinkFields, 1024>,
-    node: ChunkedArena<NodeFields, 1024>,
+/// Identifies a cleanup registered on a node, to remove it before it runs
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CleanupKey(u32);
+
+/// Marks `context` of a node as borrowed while alive, like `RefCell`'s `Ref`.
+/// This keeps the references to the context sound even when user's code running
+/// in the borrow (getters, `with` closures, ...) reaches the same node again.
+struct ContextBorrow(std::ptr::NonNull<NodeFields>);
+impl Drop for ContextBorrow {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the node is not freed while its context is borrowed,
+        // because every borrow happens under a `Hold`
+        unsafe { (*self.0.as_ptr()).borrow -= 1 };
+    }
+}
+/// `ContextBorrow` for `RefCell`'s `RefMut`
+struct ContextBorrowMut(std::ptr::NonNull<NodeFields>);
+impl Drop for ContextBorrowMut {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: same as `ContextBorrow`
+        unsafe { (*self.0.as_ptr()).borrow = 0 };
+    }
 }
 
-/// SAFETY: this crate is just intended for single-threaded use
-unsafe impl Sync for Arena {}
+const CHUNK_SIZE: usize = 1024;
 
-static ARENA: SyncUnsafeCell<Arena> = SyncUnsafeCell::new(Arena {
-    link: ChunkedArena::new_const(),
-    node: ChunkedArena::new_const(),
-});
+pub(crate) struct Arena {
+    link: ChunkedArena<LinkFields, CHUNK_SIZE>,
+    node: ChunkedArena<NodeFields, CHUNK_SIZE>,
+}
+impl Arena {
+    pub(crate) const fn new() -> Self {
+        Self {
+            link: ChunkedArena::new_const(),
+            node: ChunkedArena::new_const(),
+        }
+    }
+}
+
+/// Fill the usage of the arena of the current runtime in `stats`
+pub(crate) fn arena_stats(stats: &mut crate::Stats) {
+    with_arena_mut(|arena| {
+        stats.links = arena.link.live_count();
+        stats.node_chunks = arena.node.chunk_count();
+        stats.link_chunks = arena.link.chunk_count();
+        stats.arena_bytes = stats.node_chunks * ChunkedArena::<NodeFields, CHUNK_SIZE>::CHUNK_BYTES
+            + stats.link_chunks * ChunkedArena::<LinkFields, CHUNK_SIZE>::CHUNK_BYTES;
+    });
+}
 
 /// ## Safety
 ///
-/// Single-threaded use only
+/// Only valid on the thread that created it, ensured by `NonNull` being `!Send` and `!Sync`
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct Link(std::ptr::NonNull<LinkFields>);
 const _: () = assert!(std::mem::size_of::<Link>() == std::mem::size_of::<usize>());
@@ -85,10 +169,19 @@ const _: () = assert!(std::mem::size_of::<Option<Link>>() == std::mem::size_of::
 
 /// ## Safety
 ///
-/// Single-threaded use only
-pub struct Node<C = NodeContext>(std::ptr::NonNull<NodeFields>, std::marker::PhantomData<C>);
-const _: () = assert!(std::mem::size_of::<Node>() == std::mem::size_of::<usize>());
-const _: () = assert!(std::mem::size_of::<Option<Node>>() == std::mem::size_of::<usize>());
+/// Only valid on the thread that created it, ensured by `NonNull` being `!Send` and `!Sync`
+pub struct Node<C = NodeContext>(
+    std::ptr::NonNull<NodeFields>,
+    /// generation of the slot at the time this handle was made
+    u32,
+    /// id of the runtime owning the slot
+    u32,
+    std::marker::PhantomData<C>,
+);
+const _: () = assert!(
+    std::mem::size_of::<Node>() == std::mem::size_of::<usize>() + 2 * std::mem::size_of::<u32>()
+);
+const _: () = assert!(std::mem::size_of::<Option<Node>>() == std::mem::size_of::<Node>());
 
 /// not requiring `C: Clone`
 impl<C> Clone for Node<C> {
@@ -100,10 +193,63 @@ impl<C> Copy for Node<C> {}
 /// not requiring `C: PartialEq`
 impl<C> PartialEq for Node<C> {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0 == other.0 && self.1 == other.1 && self.2 == other.2
     }
 }
 impl<C> Eq for Node<C> {}
+impl<C> std::hash::Hash for Node<C> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.0, self.1, self.2).hash(state);
+    }
+}
+
+/// Kind of a reactive node, used in error messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Signal,
+    Computed,
+    Effect,
+    EffectScope,
+    /// the temporary subscriber of [`trigger`](crate::trigger),
+    /// alive only while its closure runs
+    Trigger,
+}
+impl std::fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Signal => "Signal",
+            Self::Computed => "Computed",
+            Self::Effect => "Effect",
+            Self::EffectScope => "EffectScope",
+            Self::Trigger => "Trigger",
+        })
+    }
+}
+
+fn alloc_node<C>(flags: Flags, context: NodeContext) -> Node<C> {
+    let runtime_id = runtime::current_id();
+    with_arena_mut(|arena| {
+        let mut generation = 0;
+        let ptr = arena.node.alloc_with(|freed| {
+            if let Some(freed) = freed {
+                generation = freed.generation;
+            }
+            NodeFields {
+                flags,
+                released: false,
+                borrow: 0,
+                generation,
+                deps: None,
+                deps_tail: None,
+                subs: None,
+                subs_tail: None,
+                context: Some(Box::new(context)),
+                extra: None,
+            }
+        });
+        Node(ptr, generation, runtime_id, std::marker::PhantomData)
+    })
+}
 
 pub(crate) struct LinkInit {
     pub(crate) version: Version,
@@ -117,7 +263,7 @@ pub(crate) struct LinkInit {
 
 impl Link {
     pub(crate) fn new(init: LinkInit) -> Self {
-        ARENA.with_borrow_mut(|arena| {
+        with_arena_mut(|arena| {
             let ptr = arena.link.alloc(LinkFields {
                 version: init.version,
                 dep: init.dep,
@@ -131,6 +277,11 @@ impl Link {
         })
     }
 
+    /// SAFETY: `self` MUST be already unlinked, and MUST NOT be used after this call.
+    pub(crate) unsafe fn free(self) {
+        with_arena_mut(|arena| unsafe { arena.link.free(self.0) });
+    }
+
     pub(crate) fn version(&self) -> Version {
         unsafe { (*self.0.as_ptr()).version }
     }
@@ -196,19 +347,109 @@ impl Link {
 }
 
 impl<C> Node<C> {
+    /// Whether this handle belongs to the current runtime.
+    /// Other methods checking the slot MUST NOT be called if not.
+    #[inline(always)]
+    pub(crate) fn is_in_current_runtime(&self) -> bool {
+        self.2 == runtime::current_id()
+    }
+
+    /// Whether the slot has been freed (and maybe reused) since this handle was made.
+    #[inline(always)]
+    pub(crate) fn is_stale(&self) -> bool {
+        // SAFETY: slots are never deallocated while the arena is alive,
+        // and freed slots are still initialized
+        unsafe { (*self.0.as_ptr()).generation != self.1 }
+    }
+
+    #[inline(always)]
+    pub(crate) fn assert_valid(&self) {
+        if !self.is_in_current_runtime() {
+            wrong_runtime_node_panic()
+        }
+        if self.is_stale() {
+            stale_node_panic()
+        }
+    }
+
+    /// Whether the node has been disposed, or its slot is already freed.
+    /// MUST be called only when [`Self::is_in_current_runtime`].
+    #[inline]
+    pub(crate) fn is_disposed(&self) -> bool {
+        self.is_stale() || unsafe { (*self.0.as_ptr()).released }
+    }
+    /// Mark the node as disposed. Returns `false` if it's already disposed.
+    #[inline]
+    pub(crate) fn mark_released(&self) -> bool {
+        if self.is_disposed() {
+            return false;
+        }
+        unsafe { (*self.0.as_ptr()).released = true };
+        true
+    }
+
+    /// Address of the slot, only for display
+    pub(crate) fn addr(&self) -> usize {
+        self.0.as_ptr() as usize
+    }
+
+    /// MUST be called only when the node is not freed.
+    pub(crate) fn node_kind(&self) -> NodeKind {
+        match unsafe { (*self.0.as_ptr()).context.as_deref() } {
+            Some(NodeContext::Signal(_)) => NodeKind::Signal,
+            Some(NodeContext::Computed(_)) => NodeKind::Computed,
+            Some(NodeContext::Effect(_)) => NodeKind::Effect,
+            Some(NodeContext::None) => NodeKind::EffectScope,
+            Some(NodeContext::Trigger) => NodeKind::Trigger,
+            None => panic!("BUG: Node is already freed"),
+        }
+    }
+
+    #[inline]
+    fn count_borrow(&self, kind: NodeKind) -> ContextBorrow {
+        let borrow = unsafe { &mut (*self.0.as_ptr()).borrow };
+        if *borrow >= BORROWED_MUT - 1 {
+            context_borrow_panic(self.describe(kind), *borrow == BORROWED_MUT)
+        }
+        *borrow += 1;
+        ContextBorrow(self.0)
+    }
+    #[inline]
+    fn count_borrow_mut(&self, kind: NodeKind) -> ContextBorrowMut {
+        let borrow = unsafe { &mut (*self.0.as_ptr()).borrow };
+        if *borrow != 0 {
+            context_borrow_panic(self.describe(kind), *borrow == BORROWED_MUT)
+        }
+        *borrow = BORROWED_MUT;
+        ContextBorrowMut(self.0)
+    }
+    /// Check the context is not borrowed mutably, without counting a borrow
+    #[inline]
+    fn check_borrow(&self, kind: NodeKind) {
+        if unsafe { (*self.0.as_ptr()).borrow } == BORROWED_MUT {
+            context_borrow_panic(self.describe(kind), true)
+        }
+    }
+    /// ## Panics
+    ///
+    /// if this node belongs to another runtime or its slot is already freed
     #[inline(always)]
     pub fn flags(&self) -> Flags {
-        unsafe { (*self.0.as_ptr()).flags }
+        self.assert_valid();
+        self.flags_unchecked()
     }
+    /// ## Panics
+    ///
+    /// if this node belongs to another runtime or its slot is already freed
     #[inline(always)]
     pub fn set_flags(&self, flags: Flags) {
-        unsafe {
-            (*self.0.as_ptr()).flags = flags;
-        }
+        self.assert_valid();
+        self.set_flags_unchecked(flags);
     }
     #[deprecated(since = "0.1.2", note = "use `add_flags` or `remove_flags` instead")]
     #[inline(always)]
     pub fn update_flags(&self, f: impl FnOnce(&mut Flags)) {
+        self.assert_valid();
         f(unsafe { &mut (*self.0.as_ptr()).flags });
     }
     /// ```rust,no_run
@@ -217,9 +458,8 @@ impl<C> Node<C> {
     /// );
     #[inline(always)]
     pub fn add_flags(&self, flags_to_add: Flags) {
-        unsafe {
-            (*self.0.as_ptr()).flags |= flags_to_add;
-        }
+        self.assert_valid();
+        self.add_flags_unchecked(flags_to_add);
     }
     /// ```rust,no_run
     /// alien_signals::get_active_sub().unwrap().remove_flags(
@@ -227,11 +467,127 @@ impl<C> Node<C> {
     /// );
     #[inline(always)]
     pub fn remove_flags(&self, flags_to_remove: Flags) {
+        self.assert_valid();
+        self.remove_flags_unchecked(flags_to_remove);
+    }
+
+    // Unchecked accessors for the runtime itself, which only reaches live nodes
+    // of the current runtime: handles are checked once at their entry points.
+    #[inline(always)]
+    pub(crate) fn flags_unchecked(&self) -> Flags {
+        unsafe { (*self.0.as_ptr()).flags }
+    }
+    #[inline(always)]
+    pub(crate) fn set_flags_unchecked(&self, flags: Flags) {
+        unsafe {
+            (*self.0.as_ptr()).flags = flags;
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn add_flags_unchecked(&self, flags_to_add: Flags) {
+        unsafe {
+            (*self.0.as_ptr()).flags |= flags_to_add;
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn remove_flags_unchecked(&self, flags_to_remove: Flags) {
         unsafe {
             (*self.0.as_ptr()).flags &= !flags_to_remove;
         }
     }
 
+    pub(crate) fn push_cleanup(&self, cleanup: Box<dyn FnOnce()>) -> CleanupKey {
+        let extra = unsafe {
+            (*self.0.as_ptr())
+                .extra
+                .get_or_insert_with(Default::default)
+        };
+        let key = CleanupKey(extra.next_cleanup_key);
+        extra.next_cleanup_key = extra.next_cleanup_key.wrapping_add(1);
+        extra.cleanups.push((key, cleanup));
+        key
+    }
+    /// Remove the cleanup of `key` if it's not run yet, returning it
+    /// to be dropped after this returns, as it may be user's closure.
+    pub(crate) fn remove_cleanup(&self, key: CleanupKey) -> Option<Box<dyn FnOnce()>> {
+        let extra = unsafe { (*self.0.as_ptr()).extra.as_deref_mut() }?;
+        let index = extra.cleanups.iter().position(|(k, _)| *k == key)?;
+        Some(extra.cleanups.remove(index).1)
+    }
+    /// Cleanups MUST be run after this returns, not while the node is borrowed,
+    /// because they may register new cleanups to the same node.
+    pub(crate) fn take_cleanups(&self) -> Vec<(CleanupKey, Box<dyn FnOnce()>)> {
+        match unsafe { (*self.0.as_ptr()).extra.as_deref_mut() } {
+            Some(extra) => std::mem::take(&mut extra.cleanups),
+            None => Vec::new(),
+        }
+    }
+    /// Number of the cleanups registered and not run yet
+    pub(crate) fn cleanup_count(&self) -> usize {
+        unsafe { (*self.0.as_ptr()).extra.as_deref() }.map_or(0, |extra| extra.cleanups.len())
+    }
+
+    pub(crate) fn set_name(&self, name: Cow<'static, str>) {
+        let extra = unsafe {
+            (*self.0.as_ptr())
+                .extra
+                .get_or_insert_with(Default::default)
+        };
+        extra.name = Some(name);
+    }
+    pub(crate) fn name(&self) -> Option<Cow<'static, str>> {
+        unsafe { (*self.0.as_ptr()).extra.as_deref() }?.name.clone()
+    }
+    #[cfg(feature = "leak-detector")]
+    pub(crate) fn set_created_at(&self, location: &'static std::panic::Location<'static>) {
+        let extra = unsafe {
+            (*self.0.as_ptr())
+                .extra
+                .get_or_insert_with(Default::default)
+        };
+        extra.created_at = Some(location);
+    }
+    #[cfg(feature = "leak-detector")]
+    pub(crate) fn created_at(&self) -> Option<&'static std::panic::Location<'static>> {
+        unsafe { (*self.0.as_ptr()).extra.as_deref() }?.created_at
+    }
+
+    /// Keep the cause made by `cause` until this effect runs, unless it's already
+    /// notified by another one
+    #[cfg(feature = "causality")]
+    pub(crate) fn set_pending_cause(&self, cause: impl FnOnce() -> crate::inspect::Cause) {
+        let extra = unsafe {
+            (*self.0.as_ptr())
+                .extra
+                .get_or_insert_with(Default::default)
+        };
+        extra.pending_cause.get_or_insert_with(|| Box::new(cause()));
+    }
+    /// Make the pending cause the one of the last run, or drop it when `ran` is false
+    #[cfg(feature = "causality")]
+    pub(crate) fn settle_cause(&self, ran: bool) {
+        if let Some(extra) = unsafe { (*self.0.as_ptr()).extra.as_deref_mut() } {
+            let pending = extra.pending_cause.take();
+            if ran {
+                extra.last_cause = pending;
+            }
+        }
+    }
+    #[cfg(feature = "causality")]
+    pub(crate) fn last_cause(&self) -> Option<crate::inspect::Cause> {
+        unsafe { (*self.0.as_ptr()).extra.as_deref() }?
+            .last_cause
+            .as_deref()
+            .cloned()
+    }
+    /// `kind` and the name of this node for messages, like `` `Computed` "total" ``
+    pub(crate) fn describe(&self, kind: NodeKind) -> String {
+        match self.name() {
+            Some(name) => format!("`{kind}` {name:?}"),
+            None => format!("`{kind}`"),
+        }
+    }
+
     #[inline]
     pub(crate) fn deps(&self) -> Option<Link> {
         unsafe { (*self.0.as_ptr()).deps }
@@ -279,22 +635,56 @@ impl<C> Node<C> {
 
 impl Node<NodeContext> {
     pub(crate) fn new(flags: Flags) -> Self {
-        ARENA.with_borrow_mut(|arena| {
-            let ptr = arena.node.alloc(NodeFields {
-                flags,
-                deps: None,
-                deps_tail: None,
-                subs: None,
-                subs_tail: None,
-                context: Box::new(NodeContext::None),
-            });
-            Node(ptr, std::marker::PhantomData)
-        })
+        alloc_node(flags, NodeContext::None)
+    }
+    pub(crate) fn new_trigger() -> Self {
+        alloc_node(Flags::WATCHING, NodeContext::Trigger)
     }
 
     #[inline]
     pub(crate) fn kind(&self) -> NodeContextKind {
-        (unsafe { &(*self.0.as_ptr()).context }).kind()
+        (unsafe { &(*self.0.as_ptr()).context })
+            .as_deref()
+            .expect("BUG: Node is already freed")
+            .kind()
+    }
+
+    /// Handles of all the nodes not disposed in the current runtime,
+    /// in the order of their slots.
+    pub(crate) fn live_nodes() -> Vec<Self> {
+        let runtime_id = runtime::current_id();
+        with_arena_mut(|arena| {
+            arena
+                .node
+                .slots()
+                .filter_map(|ptr| {
+                    let fields = unsafe { &*ptr.as_ptr() };
+                    (fields.context.is_some() && !fields.released).then_some(Node(
+                        ptr,
+                        fields.generation,
+                        runtime_id,
+                        std::marker::PhantomData,
+                    ))
+                })
+                .collect()
+        })
+    }
+
+    /// Drop the context of this node and give its slot back to the arena.
+    /// Handles to this node get stale after this.
+    ///
+    /// SAFETY: `self` MUST be already unlinked from every other node, and
+    /// MUST NOT be used after this call.
+    pub(crate) unsafe fn free(self) {
+        let (context, extra) = unsafe {
+            let fields = &mut *self.0.as_ptr();
+            fields.generation = fields.generation.wrapping_add(1);
+            (fields.context.take(), fields.extra.take())
+        };
+        with_arena_mut(|arena| unsafe { arena.node.free(self.0) });
+        // dropped after releasing the arena because user's closures in `context`
+        // may touch the arena again on drop
+        drop((context, extra));
     }
 }
 
@@ -306,43 +696,62 @@ impl Node<SignalContext> {
         init: T,
         eq_fn: impl Fn(&T, &T) -> bool + 'static,
     ) -> Self {
-        ARENA.with_borrow_mut(|arena| {
-            let init = SmallAny::new(init);
-            let context = NodeContext::Signal(SignalContext {
-                current_value: init.clone(),
-                pending_value: init,
+        let init = SmallAny::new(init);
+        alloc_node(
+            Flags::MUTABLE,
+            NodeContext::Signal(SignalContext {
+                current_value: init,
+                pending_value: None,
                 eq: Box::new(move |a, b| {
                     // SAFETY: the type is guaranteed to be T by the constructor
                     let a = unsafe { a.downcast_ref_unchecked::<T>() };
                     let b = unsafe { b.downcast_ref_unchecked::<T>() };
                     eq_fn(a, b)
                 }),
-            });
-            let ptr = arena.node.alloc(NodeFields {
-                flags: Flags::MUTABLE,
-                deps: None,
-                deps_tail: None,
-                subs: None,
-                subs_tail: None,
-                context: Box::new(context),
-            });
-            Node(ptr, std::marker::PhantomData)
-        })
+            }),
+        )
     }
 
-    /// SAFETY: `f` MUST NOT internally call `.with_context_mut` on the same `Node`.
+    /// For user's code (getters, `eq`, `with` closures, ...), counted as a borrow
+    /// while `f` runs as it may reach the same node again.
+    ///
+    /// ## Panics
+    ///
+    /// if the context is mutably borrowed, i.e. `f` is called in `.borrow_context_mut` on the same `Node`
     #[inline]
-    pub(crate) unsafe fn with_context<R>(&self, f: impl FnOnce(&SignalContext) -> R) -> R {
-        match unsafe { &*(*self.0.as_ptr()).context } {
-            NodeContext::Signal(ctx) => f(ctx),
+    pub(crate) fn borrow_context<R>(&self, f: impl FnOnce(&SignalContext) -> R) -> R {
+        let _borrow = self.count_borrow(NodeKind::Signal);
+        // SAFETY: `f` may only reach the context by the borrows counted here
+        unsafe { self.with_context_uncounted(f) }
+    }
+    /// ## Panics
+    ///
+    /// if the context is already borrowed, i.e. `f` is called in `.borrow_context` or `.borrow_context_mut` on the same `Node`
+    #[inline]
+    pub(crate) fn borrow_context_mut<R>(&self, f: impl FnOnce(&mut SignalContext) -> R) -> R {
+        let _borrow = self.count_borrow_mut(NodeKind::Signal);
+        match unsafe { (*self.0.as_ptr()).context.as_deref_mut() } {
+            Some(NodeContext::Signal(ctx)) => f(ctx),
             _ => panic!("BUG: Node is not a Signal"),
         }
     }
-    /// SAFETY: `f` MUST NOT internally call `.with_context` or `.with_context_mut` on the same `Node`.
+    /// Like `.borrow_context`, but not counted as a borrow, for the paths
+    /// the runtime runs on every read.
+    ///
+    /// SAFETY: `f` MUST NOT reach the context of this node again.
+    ///
+    /// ## Panics
+    ///
+    /// if the context is mutably borrowed by `.borrow_context_mut`
     #[inline]
-    pub(crate) unsafe fn with_context_mut<R>(&self, f: impl FnOnce(&mut SignalContext) -> R) -> R {
-        match unsafe { &mut *(*self.0.as_ptr()).context } {
-            NodeContext::Signal(ctx) => f(ctx),
+    pub(crate) unsafe fn with_context<R>(&self, f: impl FnOnce(&SignalContext) -> R) -> R {
+        self.check_borrow(NodeKind::Signal);
+        unsafe { self.with_context_uncounted(f) }
+    }
+    #[inline(always)]
+    unsafe fn with_context_uncounted<R>(&self, f: impl FnOnce(&SignalContext) -> R) -> R {
+        match unsafe { (*self.0.as_ptr()).context.as_deref() } {
+            Some(NodeContext::Signal(ctx)) => f(ctx),
             _ => panic!("BUG: Node is not a Signal"),
         }
     }
@@ -356,8 +765,9 @@ impl Node<ComputedContext> {
         getter: impl Fn(Option<&T>) -> T + 'static,
         eq_fn: impl Fn(&T, &T) -> bool + 'static,
     ) -> Self {
-        ARENA.with_borrow_mut(|arena| {
-            let context = NodeContext::Computed(ComputedContext {
+        alloc_node(
+            Flags::NONE,
+            NodeContext::Computed(ComputedContext {
                 value: None,
                 get: Box::new(move |prev_any| {
                     // SAFETY: the type is guaranteed to be T by the constructor
@@ -371,35 +781,50 @@ impl Node<ComputedContext> {
                     let b = unsafe { b.downcast_ref_unchecked::<T>() };
                     eq_fn(a, b)
                 }),
-            });
-            let ptr = arena.node.alloc(NodeFields {
-                flags: Flags::NONE,
-                deps: None,
-                deps_tail: None,
-                subs: None,
-                subs_tail: None,
-                context: Box::new(context),
-            });
-            Node(ptr, std::marker::PhantomData)
-        })
+            }),
+        )
     }
 
-    /// SAFETY: `f` MUST NOT internally call `.with_context_mut` on the same `Node`.
+    /// For user's code (getters, `eq`, `with` closures, ...), counted as a borrow
+    /// while `f` runs as it may reach the same node again.
+    ///
+    /// ## Panics
+    ///
+    /// if the context is mutably borrowed, i.e. `f` is called in `.borrow_context_mut` on the same `Node`
     #[inline]
-    pub(crate) unsafe fn with_context<R>(&self, f: impl FnOnce(&ComputedContext) -> R) -> R {
-        match unsafe { &*(*self.0.as_ptr()).context } {
-            NodeContext::Computed(ctx) => f(ctx),
+    pub(crate) fn borrow_context<R>(&self, f: impl FnOnce(&ComputedContext) -> R) -> R {
+        let _borrow = self.count_borrow(NodeKind::Computed);
+        // SAFETY: `f` may only reach the context by the borrows counted here
+        unsafe { self.with_context_uncounted(f) }
+    }
+    /// ## Panics
+    ///
+    /// if the context is already borrowed, i.e. `f` is called in `.borrow_context` or `.borrow_context_mut` on the same `Node`
+    #[inline]
+    pub(crate) fn borrow_context_mut<R>(&self, f: impl FnOnce(&mut ComputedContext) -> R) -> R {
+        let _borrow = self.count_borrow_mut(NodeKind::Computed);
+        match unsafe { (*self.0.as_ptr()).context.as_deref_mut() } {
+            Some(NodeContext::Computed(ctx)) => f(ctx),
             _ => panic!("BUG: Node is not a Computed"),
         }
     }
-    /// SAFETY: `f` MUST NOT internally call `.with_context` or `.with_context_mut` on the same `Node`.
+    /// Like `.borrow_context`, but not counted as a borrow, for the paths
+    /// the runtime runs on every read.
+    ///
+    /// SAFETY: `f` MUST NOT reach the context of this node again.
+    ///
+    /// ## Panics
+    ///
+    /// if the context is mutably borrowed by `.borrow_context_mut`
     #[inline]
-    pub(crate) unsafe fn with_context_mut<R>(
-        &self,
-        f: impl FnOnce(&mut ComputedContext) -> R,
-    ) -> R {
-        match unsafe { &mut *(*self.0.as_ptr()).context } {
-            NodeContext::Computed(ctx) => f(ctx),
+    pub(crate) unsafe fn with_context<R>(&self, f: impl FnOnce(&ComputedContext) -> R) -> R {
+        self.check_borrow(NodeKind::Computed);
+        unsafe { self.with_context_uncounted(f) }
+    }
+    #[inline(always)]
+    unsafe fn with_context_uncounted<R>(&self, f: impl FnOnce(&ComputedContext) -> R) -> R {
+        match unsafe { (*self.0.as_ptr()).context.as_deref() } {
+            Some(NodeContext::Computed(ctx)) => f(ctx),
             _ => panic!("BUG: Node is not a Computed"),
         }
     }
@@ -407,24 +832,19 @@ impl Node<ComputedContext> {
 
 impl Node<EffectContext> {
     pub(crate) fn new(f: impl Fn() + 'static) -> Self {
-        ARENA.with_borrow_mut(|arena| {
-            let context = NodeContext::Effect(EffectContext { run: Box::new(f) });
-            let ptr = arena.node.alloc(NodeFields {
-                flags: Flags::WATCHING | Flags::RECURSED_CHECK,
-                deps: None,
-                deps_tail: None,
-                subs: None,
-                subs_tail: None,
-                context: Box::new(context),
-            });
-            Node(ptr, std::marker::PhantomData)
-        })
+        alloc_node(
+            Flags::WATCHING | Flags::RECURSED_CHECK,
+            NodeContext::Effect(EffectContext { run: Box::new(f) }),
+        )
     }
 
+    /// Not counted as a borrow, unlike the ones of signals and computeds:
+    /// nothing borrows the context of an effect mutably, and disposing
+    /// the effect while it runs doesn't free it until the `Hold` ends.
     #[inline]
     pub(crate) fn with_context<R>(&self, f: impl FnOnce(&EffectContext) -> R) -> R {
-        match unsafe { &*(*self.0.as_ptr()).context } {
-            NodeContext::Effect(ctx) => f(ctx),
+        match unsafe { (*self.0.as_ptr()).context.as_deref() } {
+            Some(NodeContext::Effect(ctx)) => f(ctx),
             _ => panic!("BUG: Node is not an Effect"),
         }
     }
@@ -432,17 +852,17 @@ impl Node<EffectContext> {
 
 impl From<Node<SignalContext>> for Node<NodeContext> {
     fn from(node: Node<SignalContext>) -> Self {
-        Node(node.0, std::marker::PhantomData)
+        Node(node.0, node.1, node.2, std::marker::PhantomData)
     }
 }
 impl From<Node<ComputedContext>> for Node<NodeContext> {
     fn from(node: Node<ComputedContext>) -> Self {
-        Node(node.0, std::marker::PhantomData)
+        Node(node.0, node.1, node.2, std::marker::PhantomData)
     }
 }
 impl From<Node<EffectContext>> for Node<NodeContext> {
     fn from(node: Node<EffectContext>) -> Self {
-        Node(node.0, std::marker::PhantomData)
+        Node(node.0, node.1, node.2, std::marker::PhantomData)
     }
 }
 
@@ -450,7 +870,7 @@ impl TryFrom<Node<NodeContext>> for Node<SignalContext> {
     type Error = ();
     fn try_from(node: Node<NodeContext>) -> Result<Self, Self::Error> {
         match node.kind() {
-            NodeContextKind::Signal => Ok(Node(node.0, std::marker::PhantomData)),
+            NodeContextKind::Signal => Ok(Node(node.0, node.1, node.2, std::marker::PhantomData)),
             _ => Err(()),
         }
     }
@@ -459,7 +879,7 @@ impl TryFrom<Node<NodeContext>> for Node<ComputedContext> {
     type Error = ();
     fn try_from(node: Node<NodeContext>) -> Result<Self, Self::Error> {
         match node.kind() {
-            NodeContextKind::Computed => Ok(Node(node.0, std::marker::PhantomData)),
+            NodeContextKind::Computed => Ok(Node(node.0, node.1, node.2, std::marker::PhantomData)),
             _ => Err(()),
         }
     }
@@ -468,8 +888,30 @@ impl TryFrom<Node<NodeContext>> for Node<EffectContext> {
     type Error = ();
     fn try_from(node: Node<NodeContext>) -> Result<Self, Self::Error> {
         match node.kind() {
-            NodeContextKind::Effect => Ok(Node(node.0, std::marker::PhantomData)),
+            NodeContextKind::Effect => Ok(Node(node.0, node.1, node.2, std::marker::PhantomData)),
             _ => Err(()),
         }
     }
 }
+
+#[cold]
+#[inline(never)]
+fn wrong_runtime_node_panic() -> ! {
+    panic!("use of a `Node` out of its runtime")
+}
+
+#[cold]
+#[inline(never)]
+fn stale_node_panic() -> ! {
+    panic!("use of a `Node` whose slot is already freed")
+}
+
+#[cold]
+#[inline(never)]
+fn context_borrow_panic(node: String, is_mutably_borrowed: bool) -> ! {
+    if is_mutably_borrowed {
+        panic!("this {node} can't be read while it's being updated")
+    } else {
+        panic!("this {node} can't be updated while it's being read")
+    }
+}
@@ -10,6 +10,28 @@ impl Flags {
     pub const PENDING: Self = Self(1 << 5);
 }
 impl Flags {
+    /// Whether all of `other` are set in `self`
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Names of the flags set in `self`
+    pub(crate) fn names(self) -> impl Iterator<Item = &'static str> {
+        const NAMES: [(Flags, &str); 6] = [
+            (Flags::MUTABLE, "MUTABLE"),
+            (Flags::WATCHING, "WATCHING"),
+            (Flags::RECURSED_CHECK, "RECURSED_CHECK"),
+            (Flags::RECURSED, "RECURSED"),
+            (Flags::DIRTY, "DIRTY"),
+            (Flags::PENDING, "PENDING"),
+        ];
+        NAMES
+            .into_iter()
+            .filter(move |(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name)
+    }
+
     #[inline(always)]
     pub(crate) const fn is_zero(self) -> bool {
         self.0 == 0
@@ -19,6 +41,22 @@ impl Flags {
         self.0 != 0
     }
 }
+impl std::fmt::Debug for Flags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return f.write_str("Flags(NONE)");
+        }
+        f.write_str("Flags(")?;
+        let mut names = self.names();
+        if let Some(name) = names.next() {
+            f.write_str(name)?;
+        }
+        for name in names {
+            write!(f, " | {name}")?;
+        }
+        f.write_str(")")
+    }
+}
 impl std::ops::Not for Flags {
     type Output = Self;
     #[inline(always)]
@@ -115,12 +153,15 @@ impl Version {
     pub(crate) fn increment(&mut self) {
         self.0 += 1;
     }
+
+    pub(crate) fn get(self) -> usize {
+        self.0
+    }
 }
 
-#[derive(Clone)]
 pub(crate) enum SmallAny {
     Inline([u8; 16]),
-    Heap(std::rc::Rc<dyn std::any::Any>),
+    Heap(Box<dyn std::any::Any>),
 }
 impl SmallAny {
     pub(crate) fn new<T: std::any::Any + 'static>(value: T) -> Self {
@@ -134,7 +175,7 @@ impl SmallAny {
             std::mem::forget(value);
             Self::Inline(data)
         } else {
-            Self::Heap(std::rc::Rc::new(value))
+            Self::Heap(Box::new(value))
         }
     }
 
@@ -143,27 +184,17 @@ impl SmallAny {
     pub(crate) unsafe fn downcast_ref_unchecked<T: std::any::Any + 'static>(&self) -> &T {
         match self {
             Self::Inline(data) => unsafe { &*(data.as_ptr() as *const T) },
-            Self::Heap(rc_any) => unsafe { rc_any.downcast_ref::<T>().unwrap_unchecked() },
+            Self::Heap(box_any) => unsafe { box_any.downcast_ref::<T>().unwrap_unchecked() },
         }
     }
-}
-
-pub(crate) struct SyncUnsafeCell<T>(std::cell::UnsafeCell<T>);
-unsafe impl<T: Sync> Sync for SyncUnsafeCell<T> {}
-impl<T> SyncUnsafeCell<T> {
-    pub(crate) const fn new(value: T) -> Self {
-        Self(std::cell::UnsafeCell::new(value))
-    }
 
-    #[inline(always)]
-    pub(crate) fn with_borrow<R>(&'static self, f: impl FnOnce(&T) -> R) -> R {
-        let borrow = unsafe { &*self.0.get() };
-        f(borrow)
-    }
-    #[inline(always)]
-    pub(crate) fn with_borrow_mut<R>(&'static self, f: impl FnOnce(&mut T) -> R) -> R {
-        let borrow_mut = unsafe { &mut *self.0.get() };
-        f(borrow_mut)
+    /// SAFETY: Caller must ensure that the stored type is `T`.
+    #[inline]
+    pub(crate) unsafe fn downcast_mut_unchecked<T: std::any::Any + 'static>(&mut self) -> &mut T {
+        match self {
+            Self::Inline(data) => unsafe { &mut *(data.as_mut_ptr() as *mut T) },
+            Self::Heap(box_any) => unsafe { box_any.downcast_mut::<T>().unwrap_unchecked() },
+        }
     }
 }
 
@@ -171,6 +202,9 @@ pub(crate) struct ChunkedArena<T, const CHUNK_SIZE: usize> {
     chunks: Vec<std::ptr::NonNull<[std::mem::MaybeUninit<T>; CHUNK_SIZE]>>,
     current_chunk_index: usize,
     next_slot_index: usize,
+    /// slots given back by [`Self::free`].
+    /// They still hold initialized values, which are dropped on reuse or on arena drop.
+    free_slots: Vec<std::ptr::NonNull<T>>,
 }
 impl<T, const CHUNK_SIZE: usize> ChunkedArena<T, CHUNK_SIZE> {
     #[cold]
@@ -191,6 +225,7 @@ impl<T, const CHUNK_SIZE: usize> ChunkedArena<T, CHUNK_SIZE> {
             chunks: Vec::new(),
             current_chunk_index: 0,
             next_slot_index: 0,
+            free_slots: Vec::new(),
         }
     }
 
@@ -200,7 +235,26 @@ impl<T, const CHUNK_SIZE: usize> ChunkedArena<T, CHUNK_SIZE> {
         this
     }
 
+    #[inline]
     pub(crate) fn alloc(&mut self, value: T) -> std::ptr::NonNull<T> {
+        self.alloc_with(|_| value)
+    }
+
+    /// `init` receives the old value if a freed slot is reused.
+    pub(crate) fn alloc_with(
+        &mut self,
+        init: impl FnOnce(Option<&T>) -> T,
+    ) -> std::ptr::NonNull<T> {
+        if let Some(slot_ptr) = self.free_slots.pop() {
+            // SAFETY: freed slots are still initialized, so assigning drops the old value
+            unsafe {
+                let value = init(Some(&*slot_ptr.as_ptr()));
+                *slot_ptr.as_ptr() = value;
+            }
+            return slot_ptr;
+        }
+        let value = init(None);
+
         // for the case of `new_const`
         if self.chunks.is_empty() {
             self.make_first_chunk();
@@ -226,7 +280,43 @@ impl<T, const CHUNK_SIZE: usize> ChunkedArena<T, CHUNK_SIZE> {
         self.next_slot_index += 1;
         unsafe { std::ptr::NonNull::new_unchecked(alloced_ptr) }
     }
+
+    /// Give the slot back to the arena so that a later [`Self::alloc`] reuses it.
+    ///
+    /// SAFETY: `ptr` MUST come from `alloc` of this arena, and MUST NOT be freed twice
+    /// nor used after this call.
+    pub(crate) unsafe fn free(&mut self, ptr: std::ptr::NonNull<T>) {
+        self.free_slots.push(ptr);
+    }
+
+    /// Bytes allocated for each chunk
+    pub(crate) const CHUNK_BYTES: usize = std::mem::size_of::<T>() * CHUNK_SIZE;
+
+    pub(crate) fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Number of the slots in use, i.e. allocated and not freed
+    pub(crate) fn live_count(&self) -> usize {
+        self.current_chunk_index * CHUNK_SIZE + self.next_slot_index - self.free_slots.len()
+    }
+
+    /// Pointers to all the slots ever allocated, including the freed ones.
+    pub(crate) fn slots(&self) -> impl Iterator<Item = std::ptr::NonNull<T>> + '_ {
+        self.chunks.iter().enumerate().flat_map(move |(i, chunk)| {
+            let initialized_count = if i == self.current_chunk_index {
+                self.next_slot_index
+            } else {
+                CHUNK_SIZE
+            };
+            let chunk_head_ptr = chunk.as_ptr() as *mut T;
+            // SAFETY: `chunk_head_ptr` comes from a `NonNull`, and `j` is in the chunk
+            (0..initialized_count)
+                .map(move |j| unsafe { std::ptr::NonNull::new_unchecked(chunk_head_ptr.add(j)) })
+        })
+    }
 }
+
 impl<T, const CHUNK_SIZE: usize> Default for ChunkedArena<T, CHUNK_SIZE> {
     fn default() -> Self {
         Self::new()
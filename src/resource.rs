@@ -0,0 +1,158 @@
+use crate::future::{SpawnedFuture, spawn_cancellable};
+use crate::node::ComputedContext;
+use crate::{Computed, Effect, Node, NodeKind, Signal, batch, dispose_oper, untracked};
+use std::future::Future;
+use std::marker::PhantomData;
+
+/// Keyed async loader: fetches with the key computed by `source`, and
+/// re-fetches every time the key changes. A re-run of `source` returning
+/// an equal key doesn't re-fetch.
+///
+/// Only the response to the latest fetch is applied; the futures of the
+/// previous fetches are cancelled (dropped), so out-of-order responses are
+/// never observed. All of the reads are reactive.
+///
+/// ```
+/// use alien_signals::{signal, Resource};
+/// use futures::{executor::LocalPool, task::LocalSpawnExt};
+///
+/// let mut pool = LocalPool::new();
+/// let spawner = pool.spawner();
+///
+/// let user_id = signal(1);
+/// let user = Resource::new(
+///     move |future| spawner.spawn_local(future).unwrap(),
+///     move || user_id.get(),
+///     |id| async move { Ok::<_, String>(format!("user {id}")) },
+/// );
+/// assert!(user.loading());
+///
+/// pool.run_until_stalled();
+/// assert!(!user.loading());
+/// assert_eq!(user.latest(), Some("user 1".into()));
+///
+/// user_id.set(2);
+/// assert!(user.loading());
+/// assert_eq!(user.latest(), Some("user 1".into())); // still the latest one
+/// pool.run_until_stalled();
+/// assert_eq!(user.latest(), Some("user 2".into()));
+/// ```
+pub struct Resource<V, E> {
+    loading: Signal<bool>,
+    latest: Signal<Option<V>>,
+    error: Signal<Option<E>>,
+    /// bumped by `refetch`
+    version: Signal<u64>,
+    /// the `Computed<K>` of `source`
+    key: Node,
+    effect: Node,
+}
+impl<V, E> Clone for Resource<V, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<V, E> Copy for Resource<V, E> {}
+impl<V: 'static, E: 'static> Resource<V, E> {
+    /// `source` is tracked and `fetcher` is not.
+    /// Futures returned by `fetcher` run on `spawn`.
+    #[track_caller]
+    pub fn new<K: PartialEq + Clone + 'static, F: Future<Output = Result<V, E>> + 'static>(
+        spawn: impl Fn(SpawnedFuture) + 'static,
+        source: impl Fn() -> K + 'static,
+        fetcher: impl Fn(K) -> F + 'static,
+    ) -> Self {
+        let loading = Signal::new(true);
+        let latest = Signal::new_with_eq_fn(None, |_, _| false);
+        let error = Signal::new_with_eq_fn(None, |_, _| false);
+        let version = Signal::new(0);
+        // memoized not to re-fetch for an equal key, and not by `Computed::new`
+        // so that `report_leaks` reports the effect only
+        let key = Computed::<K>(Node::<ComputedContext>::new(move |_| source()), PhantomData);
+
+        let effect = Effect::new(move || {
+            let _ = version.get();
+            let key = key.get();
+            let future = untracked(|| {
+                loading.set(true);
+                fetcher(key)
+            });
+            spawn_cancellable(&spawn, future, move |result| {
+                batch(|| {
+                    match result {
+                        Ok(value) => {
+                            latest.set(Some(value));
+                            if error.with(Option::is_some) {
+                                error.set(None);
+                            }
+                        }
+                        Err(e) => error.set(Some(e)),
+                    }
+                    loading.set(false);
+                })
+            });
+        });
+
+        Self {
+            loading,
+            latest,
+            error,
+            version,
+            key: key.0.into(),
+            effect: effect.node.into(),
+        }
+    }
+
+    /// Whether a fetch is running
+    #[track_caller]
+    pub fn loading(&self) -> bool {
+        self.loading.get()
+    }
+
+    /// The error of the latest fetch, or `None` if it succeeded
+    #[track_caller]
+    pub fn error(&self) -> Option<E>
+    where
+        E: Clone,
+    {
+        self.error.get()
+    }
+    /// see [`Signal::with`]
+    #[track_caller]
+    pub fn with_error<R>(&self, f: impl FnOnce(Option<&E>) -> R) -> R {
+        self.error.with(|error| f(error.as_ref()))
+    }
+
+    /// The value of the latest successful fetch, kept while re-fetching
+    /// or after a failed fetch
+    #[track_caller]
+    pub fn latest(&self) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.latest.get()
+    }
+    /// see [`Signal::with`]
+    #[track_caller]
+    pub fn with_latest<R>(&self, f: impl FnOnce(Option<&V>) -> R) -> R {
+        self.latest.with(|latest| f(latest.as_ref()))
+    }
+
+    /// Fetch again with the current key, cancelling the running fetch if any
+    #[track_caller]
+    pub fn refetch(&self) {
+        self.version.set_with(|version| version.wrapping_add(1));
+    }
+
+    /// Stop fetching and cancel the running fetch.
+    /// Using this resource or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        dispose_oper(self.effect, NodeKind::Effect);
+        dispose_oper(self.key, NodeKind::Computed);
+        self.loading.dispose();
+        self.latest.dispose();
+        self.error.dispose();
+        self.version.dispose();
+    }
+}
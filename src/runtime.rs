@@ -0,0 +1,121 @@
+use crate::node::Arena;
+use crate::system::System;
+
+/// An isolated reactive graph, owning its own system state and arena.
+///
+/// Signals, computeds and effects created in [`Runtime::enter`] belong to the
+/// runtime, and using them while another runtime is current panics (or returns
+/// [`Error::WrongRuntime`](crate::Error::WrongRuntime) from `try_get`).
+/// Dropping the runtime frees all of its nodes at once.
+///
+/// Outside of any `enter`, each thread uses its own default runtime.
+///
+/// ```
+/// use alien_signals::{Runtime, Signal, Computed};
+///
+/// let runtime = Runtime::new();
+/// let double = runtime.enter(|| {
+///     let count = Signal::new(1);
+///     let double = Computed::new(move |_| count.get() * 2);
+///     assert_eq!(double.get(), 2);
+///     double
+/// });
+///
+/// assert_eq!(runtime.enter(|| double.get()), 2);
+/// assert!(double.try_get().is_err()); // not in `runtime`
+/// ```
+pub struct Runtime(Box<RuntimeState>);
+
+pub(crate) struct RuntimeState {
+    id: u32,
+    system: std::cell::UnsafeCell<System>,
+    arena: std::cell::UnsafeCell<Arena>,
+}
+
+impl RuntimeState {
+    fn new() -> Self {
+        static NEXT_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
+        // ids are never reused, so that handles of dropped runtimes are always detected
+        let id = NEXT_ID
+            .fetch_update(
+                std::sync::atomic::Ordering::Relaxed,
+                std::sync::atomic::Ordering::Relaxed,
+                |id| id.checked_add(1),
+            )
+            .expect("too many runtimes are created");
+        Self {
+            id,
+            system: std::cell::UnsafeCell::new(System::new()),
+            arena: std::cell::UnsafeCell::new(Arena::new()),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: std::cell::Cell<Option<std::ptr::NonNull<RuntimeState>>> =
+        const { std::cell::Cell::new(None) };
+    /// used when no `Runtime` is entered
+    static DEFAULT: RuntimeState = RuntimeState::new();
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self(Box::new(RuntimeState::new()))
+    }
+
+    /// Make this runtime current while running `f`.
+    /// The previously current runtime is restored after `f`, even on panic.
+    pub fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Exit(Option<std::ptr::NonNull<RuntimeState>>);
+        impl Drop for Exit {
+            fn drop(&mut self) {
+                CURRENT.set(self.0);
+            }
+        }
+
+        let _exit = Exit(CURRENT.replace(Some(std::ptr::NonNull::from(&*self.0))));
+        f()
+    }
+}
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline(always)]
+fn with_current<R>(f: impl FnOnce(&RuntimeState) -> R) -> R {
+    match CURRENT.get() {
+        // SAFETY: `Runtime::enter` keeps the runtime alive while it's current
+        Some(runtime) => f(unsafe { runtime.as_ref() }),
+        None => DEFAULT.with(f),
+    }
+}
+
+/// Whether the current runtime can be used, that is `false` while
+/// the thread-local default runtime is being destroyed on thread exit.
+pub(crate) fn is_accessible() -> bool {
+    CURRENT.get().is_some() || DEFAULT.try_with(|_| ()).is_ok()
+}
+
+#[inline(always)]
+pub(crate) fn current_id() -> u32 {
+    with_current(|rt| rt.id)
+}
+
+/// Borrows of the system MUST NOT be nested.
+#[inline(always)]
+pub(crate) fn with_system<R>(f: impl FnOnce(&System) -> R) -> R {
+    with_current(|rt| f(unsafe { &*rt.system.get() }))
+}
+/// Borrows of the system MUST NOT be nested.
+#[inline(always)]
+pub(crate) fn with_system_mut<R>(f: impl FnOnce(&mut System) -> R) -> R {
+    with_current(|rt| f(unsafe { &mut *rt.system.get() }))
+}
+
+/// Borrows of the arena MUST NOT be nested.
+#[inline(always)]
+pub(crate) fn with_arena_mut<R>(f: impl FnOnce(&mut Arena) -> R) -> R {
+    with_current(|rt| f(unsafe { &mut *rt.arena.get() }))
+}
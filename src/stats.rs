@@ -0,0 +1,126 @@
+use crate::NodeKind;
+use crate::node::Node;
+use crate::runtime::{with_system, with_system_mut};
+
+/// Statistics of the current runtime, returned by [`stats`].
+///
+/// The counts of nodes, links and chunks are the current ones, and
+/// the others are cumulative since the runtime was created, so that
+/// the work done by an operation is the difference of two `stats()`:
+///
+/// ```
+/// use alien_signals::{signal, computed, effect, stats};
+///
+/// let a = signal(1);
+/// let double = computed(move |_| a.get() * 2);
+/// let parity = computed(move |_| a.get() % 2);
+/// effect(move || println!("{} {}", double.get(), parity.get()));
+///
+/// let before = stats();
+/// a.set(3);
+/// let after = stats();
+/// assert_eq!(after.recomputations - before.recomputations, 2);
+/// assert_eq!(after.effect_runs - before.effect_runs, 1);
+///
+/// assert_eq!((after.signals, after.computeds, after.effects), (1, 2, 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct Stats {
+    /// live (not disposed) signals
+    pub signals: usize,
+    /// live computeds
+    pub computeds: usize,
+    /// live effects
+    pub effects: usize,
+    /// live effect scopes
+    pub effect_scopes: usize,
+    /// live links between a dependency and a subscriber
+    pub links: usize,
+
+    /// chunks allocated by the arena of nodes, never given back until the runtime is dropped
+    pub node_chunks: usize,
+    /// chunks allocated by the arena of links
+    pub link_chunks: usize,
+    /// bytes of all the chunks, not including the values and closures owned by nodes
+    pub arena_bytes: usize,
+
+    /// runs of the getters of computeds, including the first ones
+    pub recomputations: u64,
+    /// runs of effects, including the first ones
+    pub effect_runs: u64,
+    /// walks of dependencies checking whether a pending subscriber is dirty
+    pub check_dirty_walks: u64,
+    /// flushes of the queued effects
+    pub flushes: u64,
+}
+
+/// Cumulative counters of a runtime, kept in its system
+#[derive(Clone, Copy)]
+pub(crate) struct Counters {
+    recomputations: u64,
+    effect_runs: u64,
+    check_dirty_walks: u64,
+    flushes: u64,
+}
+impl Counters {
+    pub(crate) const fn new() -> Self {
+        Self {
+            recomputations: 0,
+            effect_runs: 0,
+            check_dirty_walks: 0,
+            flushes: 0,
+        }
+    }
+}
+
+#[inline]
+fn count(f: impl FnOnce(&mut Counters)) {
+    with_system_mut(|sys| f(&mut sys.counters));
+}
+#[inline]
+pub(crate) fn count_recomputation() {
+    count(|c| c.recomputations += 1);
+}
+#[inline]
+pub(crate) fn count_effect_run() {
+    count(|c| c.effect_runs += 1);
+}
+#[inline]
+pub(crate) fn count_check_dirty_walk() {
+    count(|c| c.check_dirty_walks += 1);
+}
+#[inline]
+pub(crate) fn count_flush() {
+    count(|c| c.flushes += 1);
+}
+
+/// Statistics of the current runtime. Use in [`Runtime::enter`](crate::Runtime::enter)
+/// to get the ones of another runtime.
+pub fn stats() -> Stats {
+    let Counters {
+        recomputations,
+        effect_runs,
+        check_dirty_walks,
+        flushes,
+    } = with_system(|sys| sys.counters);
+    let mut stats = Stats {
+        recomputations,
+        effect_runs,
+        check_dirty_walks,
+        flushes,
+        ..Stats::default()
+    };
+
+    for node in Node::live_nodes() {
+        match node.node_kind() {
+            NodeKind::Signal => stats.signals += 1,
+            NodeKind::Computed => stats.computeds += 1,
+            NodeKind::Effect => stats.effects += 1,
+            NodeKind::EffectScope => stats.effect_scopes += 1,
+            NodeKind::Trigger => {}
+        }
+    }
+    crate::node::arena_stats(&mut stats);
+    stats
+}
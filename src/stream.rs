@@ -0,0 +1,227 @@
+//! Bridges between signals and [`Stream`]s.
+
+use crate::future::{SpawnedFuture, spawn_cancellable_by};
+use crate::node::CleanupKey;
+use crate::{Computed, Effect, EffectHandle, Error, Node, NodeKind, Signal};
+use crate::{assert_handle, check_handle, runtime, untracked};
+use futures_core::Stream;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::pin::{Pin, pin};
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll, Waker};
+
+impl<T: 'static> Signal<T> {
+    /// Create a [`Stream`] yielding the current value and then each new value
+    /// of this signal, after the graph settles (when effects run).
+    ///
+    /// Values are buffered until polled. The stream ends after this signal
+    /// is disposed, and dropping the stream stops watching this signal.
+    ///
+    /// ```
+    /// use alien_signals::{signal, batch};
+    /// use futures::{executor::block_on, StreamExt};
+    ///
+    /// let count = signal(1);
+    /// let stream = count.to_stream();
+    ///
+    /// count.set(2);
+    /// batch(|| {
+    ///     count.set(3);
+    ///     count.set(4);
+    /// });
+    /// count.dispose();
+    ///
+    /// assert_eq!(block_on(stream.collect::<Vec<_>>()), [1, 2, 4]);
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// if this signal has been disposed
+    #[track_caller]
+    pub fn to_stream(&self) -> SignalStream<T>
+    where
+        T: Clone,
+    {
+        assert_handle(self.0, NodeKind::Signal);
+        let this = *self;
+        SignalStream::new(self.0.into(), NodeKind::Signal, move || this.get())
+    }
+
+    /// Create a signal set to each item of `stream` as it arrives,
+    /// driven by the future spawned on `spawn`.
+    ///
+    /// The future ends when `stream` ends, and is cancelled (dropping `stream`)
+    /// when the signal is disposed.
+    ///
+    /// ```
+    /// use alien_signals::Signal;
+    /// use futures::{executor::LocalPool, task::LocalSpawnExt, stream};
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let spawner = pool.spawner();
+    ///
+    /// let latest = Signal::from_stream(
+    ///     move |future| spawner.spawn_local(future).unwrap(),
+    ///     stream::iter([1, 2, 3]),
+    ///     0,
+    /// );
+    /// assert_eq!(latest.get(), 0);
+    ///
+    /// pool.run_until_stalled();
+    /// assert_eq!(latest.get(), 3);
+    /// ```
+    pub fn from_stream(
+        spawn: impl Fn(SpawnedFuture),
+        stream: impl Stream<Item = T> + 'static,
+        init: T,
+    ) -> Self
+    where
+        T: PartialEq,
+    {
+        let this = Signal::new(init);
+        let feed = async move {
+            let mut stream = pin!(stream);
+            while let Some(item) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                // may be disposed by the effects run by the previous item
+                if let Err(Error::Disposed(_)) = check_handle(this.0, NodeKind::Signal) {
+                    break;
+                }
+                this.set(item);
+            }
+        };
+        spawn_cancellable_by(Some(this.0.into()), &spawn, feed, |()| ());
+        this
+    }
+}
+
+impl<T: 'static> Computed<T> {
+    /// Create a [`Stream`] yielding the current value and then each new value
+    /// of this computed. See [`Signal::to_stream`].
+    ///
+    /// ## Panics
+    ///
+    /// if this computed has been disposed
+    #[track_caller]
+    pub fn to_stream(&self) -> SignalStream<T>
+    where
+        T: Clone,
+    {
+        assert_handle(self.0, NodeKind::Computed);
+        let this = *self;
+        SignalStream::new(self.0.into(), NodeKind::Computed, move || this.get())
+    }
+}
+
+/// [`Stream`] of the values of a signal or computed,
+/// created by [`Signal::to_stream`] or [`Computed::to_stream`].
+#[must_use = "streams do nothing unless polled"]
+pub struct SignalStream<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+    source: Node,
+    kind: NodeKind,
+    _effect: EffectHandle,
+}
+
+struct Shared<T> {
+    buffer: VecDeque<T>,
+    waker: Option<Waker>,
+    /// of `wake_on_dispose` on the source, removed when the stream is dropped
+    cleanup: Option<CleanupKey>,
+}
+impl<T> Shared<T> {
+    fn wake(shared: &RefCell<Self>) {
+        // not waking while borrowed, in case the waker polls synchronously
+        let waker = shared.borrow_mut().waker.take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: 'static> SignalStream<T> {
+    #[track_caller]
+    fn new(source: Node, kind: NodeKind, read: impl Fn() -> T + 'static) -> Self {
+        #[cfg(feature = "leak-detector")]
+        let created_at = std::panic::Location::caller();
+        let shared = Rc::new(RefCell::new(Shared {
+            buffer: VecDeque::new(),
+            waker: None,
+            cleanup: None,
+        }));
+
+        // not owned by the current effect, if any, to live as long as the stream
+        let effect = untracked(|| {
+            let shared = shared.clone();
+            Effect::new(move || {
+                let value = read();
+                shared.borrow_mut().buffer.push_back(value);
+                Shared::wake(&shared);
+            })
+        });
+        #[cfg(feature = "leak-detector")]
+        effect.node.set_created_at(created_at);
+        wake_on_dispose(source, Rc::downgrade(&shared));
+
+        Self {
+            shared,
+            source,
+            kind,
+            _effect: effect.into_handle(),
+        }
+    }
+}
+
+/// Wake the stream when `source` is disposed, to end it.
+fn wake_on_dispose<T: 'static>(source: Node, shared: Weak<RefCell<Shared<T>>>) {
+    let key = source.push_cleanup(Box::new({
+        let shared = shared.clone();
+        move || {
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            shared.borrow_mut().cleanup = None;
+            if source.is_disposed() {
+                Shared::wake(&shared);
+            } else {
+                // a computed runs its cleanups before every re-computation
+                wake_on_dispose(source, Rc::downgrade(&shared));
+            }
+        }
+    }));
+    if let Some(shared) = shared.upgrade() {
+        shared.borrow_mut().cleanup = Some(key);
+    }
+}
+
+impl<T> Drop for SignalStream<T> {
+    fn drop(&mut self) {
+        // not to pile up cleanups on a long-lived source
+        let cleanup = self.shared.borrow_mut().cleanup.take();
+        if let Some(key) = cleanup {
+            if runtime::is_accessible() && check_handle(self.source, self.kind).is_ok() {
+                drop(self.source.remove_cleanup(key));
+            }
+        }
+    }
+}
+
+impl<T> Stream for SignalStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(value) = shared.buffer.pop_front() {
+            return Poll::Ready(Some(value));
+        }
+        if let Err(Error::Disposed(_)) = check_handle(self.source, self.kind) {
+            return Poll::Ready(None);
+        }
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.shared.borrow().buffer.len(), None)
+    }
+}
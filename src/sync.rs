@@ -0,0 +1,391 @@
+//! Thread-safe flavour of [`Signal`], [`Computed`] and [`Effect`].
+//!
+//! All `sync` handles share one process-wide [`Runtime`](crate::Runtime)
+//! protected by a reentrant lock, so the same push-pull algorithm runs
+//! across threads. Every operation holds the lock while it runs, and
+//! effects run on the thread that triggered the flush.
+//!
+//! ```
+//! use alien_signals::sync::{Signal, Computed, Effect};
+//!
+//! let count = Signal::new(1);
+//! let double = Computed::new(move |_| count.get() * 2);
+//!
+//! std::thread::spawn(move || count.set(2)).join().unwrap();
+//! assert_eq!(double.get(), 4);
+//! ```
+//!
+//! The lock is held while getters and effects run, so blocking in them
+//! on another thread that uses `sync` handles deadlocks.
+//!
+//! Getters, effects and cleanups of `sync` handles run in the shared runtime,
+//! so they MUST create nodes and register cleanups with this module's APIs.
+//! The thread-local ones like [`crate::Effect::new`] or [`crate::on_cleanup`]
+//! panic there, because their values and closures may not be `Send`.
+
+use crate::Error;
+use std::sync::{Condvar, LazyLock, Mutex, PoisonError};
+use std::thread::ThreadId;
+
+struct Shared {
+    lock: ReentrantLock,
+    runtime: crate::Runtime,
+}
+// SAFETY: `runtime` is only used while `lock` is held, and every value and
+// closure stored in it is `Send`: they're all given to this module's APIs,
+// which require `Send`, as the thread-local APIs storing anything in a runtime
+// panic by `assert_not_shared` while `runtime` is current, unless `admit`ted.
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+static SHARED: LazyLock<Shared> = LazyLock::new(|| {
+    let runtime = crate::Runtime::new();
+    runtime.enter(|| crate::runtime::with_system_mut(|sys| sys.sync.is_shared = true));
+    Shared {
+        lock: ReentrantLock::new(),
+        runtime,
+    }
+});
+
+/// Lock the shared runtime and make it current while running `f`.
+fn with_shared<R>(f: impl FnOnce() -> R) -> R {
+    let shared = &*SHARED;
+    let _guard = shared.lock.lock();
+    shared.runtime.enter(f)
+}
+
+/// State of a runtime about sharing, kept in its system
+pub(crate) struct SyncState {
+    /// whether it's the runtime shared by `sync` handles
+    is_shared: bool,
+    /// whether the next `assert_not_shared` is called by this module
+    admitted: bool,
+}
+impl SyncState {
+    pub(crate) const fn new() -> Self {
+        Self {
+            is_shared: false,
+            admitted: false,
+        }
+    }
+}
+
+/// Run a thread-local API storing `Send` values or closures in the shared runtime.
+/// `f` MUST call `assert_not_shared` before running anything given by the user.
+fn admit<R>(f: impl FnOnce() -> R) -> R {
+    crate::runtime::with_system_mut(|sys| sys.sync.admitted = true);
+    f()
+}
+
+/// Called by the thread-local APIs storing values or closures in the current runtime,
+/// panicking if it's the shared one, unless called in `admit`.
+#[track_caller]
+pub(crate) fn assert_not_shared(api: &str) {
+    let is_denied = crate::runtime::with_system_mut(|sys| {
+        sys.sync.is_shared && !std::mem::take(&mut sys.sync.admitted)
+    });
+    if is_denied {
+        panic!(
+            "`{api}` can't be used in getters, effects and cleanups of `sync` handles, \
+             use the one in `alien_signals::sync` instead"
+        )
+    }
+}
+
+/// Reentrant because getters and effects use `sync` handles
+/// on the thread already holding the lock.
+struct ReentrantLock {
+    owner: Mutex<Option<(ThreadId, usize)>>,
+    released: Condvar,
+}
+struct ReentrantLockGuard<'l>(&'l ReentrantLock);
+
+impl ReentrantLock {
+    fn new() -> Self {
+        Self {
+            owner: Mutex::new(None),
+            released: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> ReentrantLockGuard<'_> {
+        let me = std::thread::current().id();
+        // the state is always consistent, so just ignore poisoning
+        let mut owner = self.owner.lock().unwrap_or_else(PoisonError::into_inner);
+        loop {
+            match &mut *owner {
+                None => {
+                    *owner = Some((me, 1));
+                    break;
+                }
+                Some((id, depth)) if *id == me => {
+                    *depth += 1;
+                    break;
+                }
+                Some(_) => {
+                    owner = self
+                        .released
+                        .wait(owner)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+        ReentrantLockGuard(self)
+    }
+}
+impl Drop for ReentrantLockGuard<'_> {
+    fn drop(&mut self) {
+        let mut owner = self.0.owner.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, depth) = owner
+            .as_mut()
+            .expect("BUG: `ReentrantLock` is not owned in guard's drop");
+        *depth -= 1;
+        if *depth == 0 {
+            *owner = None;
+            drop(owner);
+            self.0.released.notify_one();
+        }
+    }
+}
+
+/// see [`crate::batch`]
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    with_shared(|| crate::batch(f))
+}
+
+/// see [`crate::untracked`]
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    with_shared(|| crate::untracked(f))
+}
+
+/// see [`crate::on_cleanup`]
+pub fn on_cleanup(cleanup: impl FnOnce() + Send + 'static) {
+    with_shared(|| admit(|| crate::on_cleanup(cleanup)))
+}
+
+/// alias of [`Signal::new`]
+#[track_caller]
+pub fn signal<T: PartialEq + Send + 'static>(init: T) -> Signal<T> {
+    Signal::new(init)
+}
+
+/// `Send + Sync` counterpart of [`crate::Signal`].
+pub struct Signal<T>(crate::Signal<T>);
+// SAFETY: the node belongs to the shared runtime and is only used while its
+// lock is held, and the value and `eq_fn` stored in it are `Send`
+unsafe impl<T: Send> Send for Signal<T> {}
+unsafe impl<T: Send> Sync for Signal<T> {}
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Signal<T> {}
+impl<T: Send + 'static> Signal<T> {
+    #[track_caller]
+    pub fn new(init: T) -> Self
+    where
+        T: PartialEq,
+    {
+        with_shared(|| Self(admit(|| crate::Signal::new(init))))
+    }
+    #[track_caller]
+    pub fn new_with_eq_fn(init: T, eq_fn: impl Fn(&T, &T) -> bool + Send + 'static) -> Self {
+        with_shared(|| Self(admit(|| crate::Signal::new_with_eq_fn(init, eq_fn))))
+    }
+    /// see [`crate::Signal::named`]
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        with_shared(|| Self(self.0.named(name)))
+    }
+
+    /// ## Panics
+    ///
+    /// if this signal has been disposed
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.get())
+    }
+    /// non-panicking variant of [`Signal::get`]
+    pub fn try_get(&self) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.try_get())
+    }
+    /// see [`crate::Signal::peek`]
+    #[track_caller]
+    pub fn peek(&self) -> T
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.peek())
+    }
+    /// see [`crate::Signal::with`]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        with_shared(|| self.0.with(f))
+    }
+
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        with_shared(|| self.0.set(value))
+    }
+
+    /// set with current value
+    #[track_caller]
+    pub fn set_with(&self, f: impl FnOnce(&T) -> T) {
+        with_shared(|| self.0.set_with(f))
+    }
+
+    #[track_caller]
+    pub fn set_mut(&self, f: impl FnOnce(&mut T)) {
+        with_shared(|| self.0.set_mut(f))
+    }
+
+    /// Unsubscribe all subscribers from this signal and free it.
+    /// Using this signal or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        with_shared(|| self.0.dispose())
+    }
+}
+
+/// alias of [`Computed::new`]
+#[track_caller]
+pub fn computed<T: PartialEq + Send + 'static>(
+    getter: impl Fn(Option<&T>) -> T + Send + 'static,
+) -> Computed<T> {
+    Computed::new(getter)
+}
+
+/// `Send + Sync` counterpart of [`crate::Computed`].
+///
+/// The getter runs on the thread that reads this computed.
+pub struct Computed<T>(crate::Computed<T>);
+// SAFETY: the node belongs to the shared runtime and is only used while its
+// lock is held, and the value and closures stored in it are `Send`
+unsafe impl<T: Send> Send for Computed<T> {}
+unsafe impl<T: Send> Sync for Computed<T> {}
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Computed<T> {}
+impl<T: Send + 'static> Computed<T> {
+    #[track_caller]
+    pub fn new(getter: impl Fn(Option<&T>) -> T + Send + 'static) -> Self
+    where
+        T: PartialEq,
+    {
+        // `Location::caller` of the inner one is in the closure below
+        #[cfg(feature = "leak-detector")]
+        let created_at = std::panic::Location::caller();
+        with_shared(|| {
+            let computed = admit(|| crate::Computed::new(getter));
+            #[cfg(feature = "leak-detector")]
+            computed.0.set_created_at(created_at);
+            Self(computed)
+        })
+    }
+    #[track_caller]
+    pub fn new_with_eq(
+        getter: impl Fn(Option<&T>) -> T + Send + 'static,
+        eq_fn: impl Fn(&T, &T) -> bool + Send + 'static,
+    ) -> Self {
+        #[cfg(feature = "leak-detector")]
+        let created_at = std::panic::Location::caller();
+        with_shared(|| {
+            let computed = admit(|| crate::Computed::new_with_eq(getter, eq_fn));
+            #[cfg(feature = "leak-detector")]
+            computed.0.set_created_at(created_at);
+            Self(computed)
+        })
+    }
+    /// see [`crate::Computed::named`]
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        with_shared(|| Self(self.0.named(name)))
+    }
+
+    /// ## Panics
+    ///
+    /// if this computed has been disposed
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.get())
+    }
+    /// non-panicking variant of [`Computed::get`]
+    pub fn try_get(&self) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.try_get())
+    }
+    /// see [`crate::Computed::peek`]
+    #[track_caller]
+    pub fn peek(&self) -> T
+    where
+        T: Clone,
+    {
+        with_shared(|| self.0.peek())
+    }
+    /// see [`crate::Computed::with`]
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        with_shared(|| self.0.with(f))
+    }
+
+    /// Unsubscribe this computed from its deps and subscribers, and free it.
+    /// Using this computed or its copies after this panics.
+    #[track_caller]
+    pub fn dispose(self) {
+        with_shared(|| self.0.dispose())
+    }
+}
+
+/// alias of [`Effect::new`]
+#[track_caller]
+pub fn effect(f: impl Fn() + Send + 'static) -> Effect {
+    Effect::new(f)
+}
+
+/// `Send + Sync` counterpart of [`crate::Effect`].
+///
+/// `f` runs on the thread creating this effect first, and then on
+/// the thread setting a dependency of this effect.
+pub struct Effect(crate::Effect);
+// SAFETY: `crate::Effect` only holds the node, that belongs to the shared
+// runtime and is only used while its lock is held
+unsafe impl Send for Effect {}
+unsafe impl Sync for Effect {}
+impl Effect {
+    #[track_caller]
+    pub fn new(f: impl Fn() + Send + 'static) -> Self {
+        #[cfg(feature = "leak-detector")]
+        let created_at = std::panic::Location::caller();
+        with_shared(|| {
+            let effect = admit(|| crate::Effect::new(f));
+            #[cfg(feature = "leak-detector")]
+            effect.node.set_created_at(created_at);
+            Self(effect)
+        })
+    }
+    /// see [`crate::Effect::named`]
+    #[track_caller]
+    pub fn named(self, name: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        with_shared(|| Self(self.0.named(name)))
+    }
+
+    #[track_caller]
+    pub fn dispose(self) {
+        with_shared(|| self.0.dispose())
+    }
+}
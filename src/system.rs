@@ -1,63 +1,321 @@
 use crate::node::{EffectContext, Link, LinkInit, Node};
-use crate::primitive::{Flags, Queue, Stack, SyncUnsafeCell, Version};
+use crate::primitive::{Flags, Queue, Stack, Version};
+use crate::runtime::{with_system, with_system_mut};
 
-struct System {
+pub(crate) struct System {
     cycle: Version,
     batch_depth: usize,
     active_sub: Option<Node>,
     queued: Queue<Node<EffectContext>>,
+    hold_depth: usize,
+    released_nodes: Stack<Node>,
+    released_links: Stack<Link>,
+    scheduler: Option<std::rc::Rc<dyn Fn(FlushRequest)>>,
+    flush_requested: bool,
+    pub(crate) counters: crate::stats::Counters,
+    /// computeds whose getters are running, innermost last
+    computing: Vec<Node>,
+    /// where the signal being set is set, for the causes of the effects it notifies
+    #[cfg(feature = "causality")]
+    pub(crate) setting_at: Option<&'static std::panic::Location<'static>>,
+    /// state of the runtime shared by `sync` handles
+    #[cfg(feature = "sync")]
+    pub(crate) sync: crate::sync::SyncState,
+}
+impl System {
+    pub(crate) const fn new() -> Self {
+        Self {
+            cycle: Version::new(),
+            batch_depth: 0,
+            active_sub: None,
+            queued: Queue::new(),
+            hold_depth: 0,
+            released_nodes: Stack::new(),
+            released_links: Stack::new(),
+            scheduler: None,
+            flush_requested: false,
+            counters: crate::stats::Counters::new(),
+            computing: Vec::new(),
+            #[cfg(feature = "causality")]
+            setting_at: None,
+            #[cfg(feature = "sync")]
+            sync: crate::sync::SyncState::new(),
+        }
+    }
 }
-
-/// SAFETY: This crate is just intended for single-threaded use.
-unsafe impl Sync for System {}
-
-static SYSTEM: SyncUnsafeCell<System> = SyncUnsafeCell::new(System {
-    cycle: Version::new(),
-    batch_depth: 0,
-    active_sub: None,
-    queued: Queue::new(),
-});
 
 #[inline(always)]
 pub fn set_active_sub(sub: Option<Node>) -> Option<Node> {
-    SYSTEM.with_borrow_mut(|sys| std::mem::replace(&mut sys.active_sub, sub))
+    if let Some(sub) = sub {
+        sub.assert_valid();
+    }
+    with_system_mut(|sys| std::mem::replace(&mut sys.active_sub, sub))
 }
 #[inline]
 pub fn get_active_sub() -> Option<Node> {
-    SYSTEM.with_borrow(|sys| sys.active_sub)
+    with_system(|sys| sys.active_sub)
 }
 
 #[inline]
 pub fn get_batch_depth() -> usize {
-    SYSTEM.with_borrow(|sys| sys.batch_depth)
+    with_system(|sys| sys.batch_depth)
 }
 #[inline]
 pub fn start_batch() {
-    SYSTEM.with_borrow_mut(|sys| sys.batch_depth += 1);
+    with_system_mut(|sys| sys.batch_depth += 1);
 }
 #[inline]
 pub fn end_batch() {
-    let is_zero = SYSTEM.with_borrow_mut(|sys| {
+    let is_zero = with_system_mut(|sys| {
         sys.batch_depth -= 1;
         sys.batch_depth == 0
     });
     if is_zero {
-        super::flush();
+        schedule_flush();
+    }
+}
+
+/// Run `f` in a batch, flushing effects once after it.
+///
+/// ```
+/// use alien_signals::{signal, effect, batch};
+///
+/// let a = signal(1);
+/// let b = signal(2);
+/// effect(move || println!("{}", a.get() + b.get())); // 3
+///
+/// let sum = batch(|| {
+///     a.set(10);
+///     b.set(20);
+///     a.get() + b.get()
+/// }); // 30, printed only once
+/// assert_eq!(sum, 30);
+/// ```
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    let _guard = BatchGuard::new();
+    f()
+}
+
+/// Starts a batch on creation and ends it on drop.
+///
+/// The batch is ended even when unwinding, so that a panic in it doesn't
+/// stop the runtime flushing effects. Effects queued in the batch are not
+/// run while unwinding, but in the next flush.
+#[must_use = "the batch ends immediately if the guard is not bound"]
+pub struct BatchGuard(
+    /// `!Send` and `!Sync`, because the batch belongs to the current thread's runtime
+    std::marker::PhantomData<*const ()>,
+);
+impl BatchGuard {
+    pub fn new() -> Self {
+        start_batch();
+        Self(std::marker::PhantomData)
+    }
+}
+impl Default for BatchGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            with_system_mut(|sys| sys.batch_depth -= 1);
+        } else {
+            end_batch();
+        }
+    }
+}
+
+/// Passed to the scheduler set by [`set_scheduler`] when effects are queued.
+///
+/// Flushing MUST be done in the runtime that requested it. Dropping it
+/// without flushing (or a panic in the scheduler) lets the next set request
+/// a flush again.
+#[must_use = "queued effects don't run until flushed"]
+pub struct FlushRequest(
+    /// `!Send` and `!Sync`, because the request belongs to the current thread's runtime
+    std::marker::PhantomData<*const ()>,
+);
+impl FlushRequest {
+    /// Same as [`flush_effects`]
+    pub fn flush(self) {
+        // not to clear a request made by an effect run in this flush
+        std::mem::forget(self);
+        flush_effects();
+    }
+}
+impl Drop for FlushRequest {
+    fn drop(&mut self) {
+        if crate::runtime::is_accessible() {
+            with_system_mut(|sys| sys.flush_requested = false);
+        }
+    }
+}
+
+/// Set the scheduler of the current runtime, that decides when to run
+/// effects queued by setting signals.
+///
+/// By default effects run synchronously in the setter (or at the end of
+/// the batch). With a scheduler, `scheduler` just receives a [`FlushRequest`]
+/// and the effects run when it's flushed or [`flush_effects`] is called.
+/// Requests are coalesced until the next flush.
+///
+/// ```
+/// use alien_signals::{signal, effect, set_scheduler, FlushRequest};
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// let requests = Rc::new(RefCell::new(Vec::<FlushRequest>::new()));
+/// set_scheduler({
+///     let requests = requests.clone();
+///     move |request| requests.borrow_mut().push(request)
+/// });
+///
+/// let count = signal(0);
+/// effect(move || println!("{}", count.get())); // 0
+///
+/// count.set(1); // prints nothing
+/// count.set(2); // prints nothing
+/// for request in requests.take() {
+///     request.flush(); // 2
+/// }
+/// ```
+#[track_caller]
+pub fn set_scheduler(scheduler: impl Fn(FlushRequest) + 'static) {
+    #[cfg(feature = "sync")]
+    crate::sync::assert_not_shared("set_scheduler");
+    with_system_mut(|sys| sys.scheduler = Some(std::rc::Rc::new(scheduler)));
+}
+/// Remove the scheduler of the current runtime, running effects synchronously again.
+/// Effects already queued run in the next flush.
+pub fn remove_scheduler() {
+    let scheduler = with_system_mut(|sys| sys.scheduler.take());
+    // may run user's `Drop`
+    drop(scheduler);
+}
+
+/// Run the effects queued in the current runtime.
+pub fn flush_effects() {
+    with_system_mut(|sys| sys.flush_requested = false);
+    super::flush();
+}
+
+/// Flush now, or request the scheduler to flush if any.
+pub(crate) fn schedule_flush() {
+    let Some(scheduler) = with_system(|sys| sys.scheduler.clone()) else {
+        return super::flush();
+    };
+    let should_request = with_system_mut(|sys| {
+        let should_request = !sys.flush_requested && sys.queued.length() > 0;
+        if should_request {
+            sys.flush_requested = true;
+        }
+        should_request
+    });
+    if should_request {
+        scheduler(FlushRequest(std::marker::PhantomData));
     }
 }
 
+/// Marks a computed as computing until dropped, even when its getter panics
+pub(crate) struct Computing(());
+#[inline]
+pub(crate) fn start_computing(computed: Node) -> Computing {
+    with_system_mut(|sys| sys.computing.push(computed));
+    Computing(())
+}
+impl Drop for Computing {
+    #[inline]
+    fn drop(&mut self) {
+        if crate::runtime::is_accessible() {
+            with_system_mut(|sys| sys.computing.pop());
+        }
+    }
+}
+/// If `computed` is computing, the cycle reading it again:
+/// `computed`, the computeds read in turn from its getter, and `computed`
+pub(crate) fn cycle_of(computed: Node) -> Option<Vec<Node>> {
+    with_system(|sys| {
+        let start = sys.computing.iter().rposition(|&node| node == computed)?;
+        Some(
+            sys.computing[start..]
+                .iter()
+                .copied()
+                .chain([computed])
+                .collect(),
+        )
+    })
+}
+
 #[inline]
 pub(crate) fn increment_cycle() {
-    SYSTEM.with_borrow_mut(|sys| sys.cycle.increment());
+    with_system_mut(|sys| sys.cycle.increment());
 }
 #[inline]
 pub(crate) fn get_cycle() -> Version {
-    SYSTEM.with_borrow(|sys| sys.cycle)
+    with_system(|sys| sys.cycle)
 }
 
 #[inline]
 pub(crate) fn with_queued<T>(f: impl Fn(&mut Queue<Node<EffectContext>>) -> T) -> T {
-    SYSTEM.with_borrow_mut(|sys| f(&mut sys.queued))
+    with_system_mut(|sys| f(&mut sys.queued))
+}
+
+/// While any `Hold` is alive, released nodes and links are not freed but
+/// just stashed, because they may still be referenced from the call stack
+/// (e.g. an effect disposing itself in its own body, or `check_dirty` walking
+/// links while a computed getter purges them).
+/// They're freed when the outermost `Hold` is dropped out of any batch.
+pub(crate) struct Hold(());
+
+#[inline]
+pub(crate) fn hold() -> Hold {
+    with_system_mut(|sys| sys.hold_depth += 1);
+    Hold(())
+}
+impl Drop for Hold {
+    #[inline]
+    fn drop(&mut self) {
+        let is_idle = with_system(|sys| sys.hold_depth == 1 && sys.batch_depth == 0);
+        if is_idle {
+            // still holding here, so that nodes and links released while
+            // reclaiming are just stashed and handled in the same loop
+            reclaim();
+        }
+        with_system_mut(|sys| sys.hold_depth -= 1);
+    }
+}
+
+/// Mark `node` as disposed, run its cleanups and free it when no `Hold` is alive.
+/// Handles to `node` are detected as disposed after this.
+/// Does nothing if `node` is already disposed.
+pub(crate) fn release_node(node: Node) {
+    if node.mark_released() {
+        let _hold = hold();
+        super::run_cleanups(node);
+        with_system_mut(|sys| sys.released_nodes.push(node));
+    }
+}
+
+fn reclaim() {
+    // freeing a node may run user's `Drop`s, that may release more nodes and links,
+    // so pop one by one without keeping the system borrowed
+    while let Some(node) = with_system_mut(|sys| sys.released_nodes.pop()) {
+        // a released node may be linked again by the code that was running it
+        // (e.g. a computed disposed in its own getter), so detach it for good here
+        node.set_deps_tail(None);
+        super::purge_deps(node);
+        while let Some(sub) = node.subs() {
+            unlink(sub, sub.sub());
+        }
+        // SAFETY: `node` is unlinked from every other node above, and the
+        // handles to it are checked by the generation after this
+        unsafe { node.free() }
+    }
+    while let Some(link) = with_system_mut(|sys| sys.released_links.pop()) {
+        // SAFETY: `link` is unlinked in `unlink` and no longer referenced
+        unsafe { link.free() }
+    }
 }
 
 pub(crate) fn link(dep: Node, sub: Node, version: Version) {
@@ -153,33 +411,40 @@ pub(crate) fn unlink(link: Link, sub: Node) -> Option<Link> {
         }
     }
 
+    with_system_mut(|sys| sys.released_links.push(link));
+
     next_dep
 }
 
 pub(crate) fn propagate(mut link: Link) {
+    let _span = crate::trace::span!("propagate", link.dep());
     let mut next = link.next_sub();
     let mut stack = Stack::<Option<Link>>::new();
+    #[cfg(feature = "causality")]
+    let source = link.dep();
 
     'top: loop {
         let sub = link.sub();
-        let mut flags = sub.flags();
+        let mut flags = sub.flags_unchecked();
 
         if (flags & (Flags::RECURSED_CHECK | Flags::RECURSED | Flags::DIRTY | Flags::PENDING))
             .is_zero()
         {
-            sub.set_flags(flags | Flags::PENDING);
+            sub.set_flags_unchecked(flags | Flags::PENDING);
         } else if (flags & (Flags::RECURSED_CHECK | Flags::RECURSED)).is_zero() {
             flags = Flags::NONE;
         } else if (flags & Flags::RECURSED_CHECK).is_zero() {
-            sub.set_flags((flags & !Flags::RECURSED) | Flags::PENDING);
+            sub.set_flags_unchecked((flags & !Flags::RECURSED) | Flags::PENDING);
         } else if (flags & (Flags::DIRTY | Flags::PENDING)).is_zero() && is_valid_link(link, sub) {
-            sub.set_flags(flags | (Flags::RECURSED | Flags::PENDING));
+            sub.set_flags_unchecked(flags | (Flags::RECURSED | Flags::PENDING));
             flags &= Flags::MUTABLE;
         } else {
             flags = Flags::NONE;
         }
 
         if (flags & Flags::WATCHING).is_nonzero() {
+            #[cfg(feature = "causality")]
+            record_cause(sub, link.dep(), source);
             super::notify(
                 sub.try_into()
                     .expect("BUG: `sub` is not effect in `propagate`"),
@@ -215,16 +480,58 @@ pub(crate) fn propagate(mut link: Link) {
     }
 }
 
+/// Record on `effect` that `source` reaches it through `dep`, finding
+/// the computeds between them by walking the deps back from `dep`.
+#[cfg(feature = "causality")]
+fn record_cause(effect: Node, dep: Node, source: Node) {
+    use crate::inspect::{Cause, NodeId};
+
+    effect.set_pending_cause(|| {
+        // a path of deps from `source` to `dep`, which is one `propagate` went
+        // through as `source` reaches every node depending on it
+        let mut via = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut stack = vec![(dep, dep.deps())];
+        while let Some((node, link)) = stack.last_mut() {
+            if *node == source {
+                let len = stack.len() - 1;
+                via.extend(stack[..len].iter().map(|(node, _)| NodeId(*node)));
+                break;
+            }
+            match *link {
+                Some(some_link) => {
+                    *link = some_link.next_dep();
+                    let dep = some_link.dep();
+                    if visited.insert(dep) {
+                        stack.push((dep, dep.deps()));
+                    }
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        via.reverse();
+        Cause {
+            signal: NodeId(source),
+            via,
+            location: with_system(|sys| sys.setting_at),
+        }
+    });
+}
+
 pub(crate) fn check_dirty(mut link: Link, mut sub: Node) -> bool {
+    let span = crate::trace::span!("check_dirty", sub, dirty);
+    crate::stats::count_check_dirty_walk();
     let mut stack = Stack::<Link>::new();
     let mut check_depth = 0;
     let mut dirty = false;
 
     'top: loop {
         let dep = link.dep();
-        let flags = dep.flags();
+        let flags = dep.flags_unchecked();
 
-        if (sub.flags() & Flags::DIRTY).is_nonzero() {
+        if (sub.flags_unchecked() & Flags::DIRTY).is_nonzero() {
             dirty = true;
         } else if (flags & (Flags::MUTABLE | Flags::DIRTY)) == (Flags::MUTABLE | Flags::DIRTY) {
             if super::update(dep) {
@@ -279,7 +586,8 @@ pub(crate) fn check_dirty(mut link: Link, mut sub: Node) -> bool {
                 }
                 dirty = false;
             } else {
-                sub.remove_flags(Flags::PENDING);
+                crate::trace::event!("deps unchanged, skipped recomputation", sub);
+                sub.remove_flags_unchecked(Flags::PENDING);
             }
 
             sub = link.sub();
@@ -294,6 +602,7 @@ pub(crate) fn check_dirty(mut link: Link, mut sub: Node) -> bool {
             continue;
         }
 
+        crate::trace::record!(span, dirty, dirty);
         return dirty;
     }
 }
@@ -302,10 +611,10 @@ pub(crate) fn check_dirty(mut link: Link, mut sub: Node) -> bool {
 pub(crate) fn shallow_propagate(mut link: Link) {
     loop {
         let sub = link.sub();
-        let flags = sub.flags();
+        let flags = sub.flags_unchecked();
 
         if (flags & (Flags::PENDING | Flags::DIRTY)) == Flags::PENDING {
-            sub.add_flags(Flags::DIRTY);
+            sub.add_flags_unchecked(Flags::DIRTY);
             if (flags & (Flags::WATCHING | Flags::RECURSED_CHECK)) == Flags::WATCHING {
                 super::notify(
                     sub.try_into()
@@ -0,0 +1,71 @@
+//! Spans and events for the `tracing` feature, compiled out without it.
+//!
+//! Every span has a `node` field labelling the node it's about,
+//! by the name given by `.named()` or by its kind and address.
+
+use crate::Node;
+
+/// Label of a node in traces and messages, also used without the `tracing` feature
+pub(crate) struct Label(pub(crate) Node);
+impl std::fmt::Display for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.name() {
+            Some(name) => f.write_str(&name),
+            None => write!(f, "{}@{:#x}", self.0.node_kind(), self.0.addr()),
+        }
+    }
+}
+
+/// `span!("name", node, field...)` enters a trace-level span, and
+/// `record!(span, field, value)` fills a field declared in it.
+/// Spans without `node` are debug-level.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal; $($field:ident),*) => {
+        tracing::debug_span!($name $(, $field = tracing::field::Empty)*).entered()
+    };
+    ($name:literal, $node:expr $(, $field:ident)*) => {
+        tracing::trace_span!(
+            $name,
+            node = %$crate::trace::Label($node.into())
+            $(, $field = tracing::field::Empty)*
+        )
+        .entered()
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal; $($field:ident),*) => {
+        ()
+    };
+    ($name:literal, $node:expr $(, $field:ident)*) => {
+        ()
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! record {
+    ($span:expr, $field:ident, $value:expr) => {
+        $span.record(stringify!($field), $value)
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! record {
+    ($span:expr, $field:ident, $value:expr) => {
+        let _ = (&$span, &$value);
+    };
+}
+
+/// `event!("message", node)` emits a trace-level event about `node`.
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($message:literal, $node:expr) => {
+        tracing::trace!(node = %$crate::trace::Label($node.into()), $message)
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($message:literal, $node:expr) => {};
+}
+
+pub(crate) use {event, record, span};
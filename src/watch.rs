@@ -0,0 +1,91 @@
+use crate::{Effect, NodeKind, check_handle, dispose_oper, get_active_sub, untracked};
+use std::rc::Rc;
+
+pub struct WatchOptions<T> {
+    /// Run `callback` immediately with no old value, instead of
+    /// waiting for the first change
+    pub immediate: bool,
+    /// Dispose the watcher after the first run of `callback`
+    pub once: bool,
+    /// `callback` is not run when this returns `true` for the new and old values
+    pub equals: Box<dyn Fn(&T, &T) -> bool>,
+    /// Receives the jobs running `callback`, e.g. to defer them to an event loop.
+    /// Jobs of a watcher disposed before they run do nothing.
+    pub scheduler: Box<dyn Fn(Box<dyn FnOnce()>)>,
+}
+impl<T: PartialEq + 'static> Default for WatchOptions<T> {
+    fn default() -> Self {
+        Self {
+            immediate: false,
+            once: false,
+            equals: Box::new(T::eq),
+            scheduler: Box::new(|job| job()),
+        }
+    }
+}
+
+/// Run `callback` with the new and old values every time `source` changes.
+///
+/// `source` is tracked like the body of an effect, and `callback` is run
+/// untracked. The returned effect stops watching when disposed.
+///
+/// ```
+/// use alien_signals::{signal, watch, WatchOptions};
+///
+/// let count = signal(1);
+/// let watcher = watch(
+///     move || count.get(),
+///     |new, old| println!("{old:?} -> {new}"),
+///     WatchOptions::default(),
+/// ); // prints nothing
+///
+/// count.set(2); // Some(1) -> 2
+/// watcher.dispose();
+/// count.set(3); // prints nothing
+/// ```
+#[track_caller]
+pub fn watch<T: 'static>(
+    source: impl Fn() -> T + 'static,
+    callback: impl Fn(&T, Option<&T>) + 'static,
+    WatchOptions {
+        immediate,
+        once,
+        equals,
+        scheduler,
+    }: WatchOptions<T>,
+) -> Effect {
+    let callback = Rc::new(callback);
+    let old_value = std::cell::RefCell::new(None::<Rc<T>>);
+    let is_first_run = std::cell::Cell::new(true);
+
+    Effect::new(move || {
+        let new_value = Rc::new(source());
+        let old_value = {
+            let mut old_value = old_value.borrow_mut();
+            if is_first_run.replace(false) && !immediate {
+                *old_value = Some(new_value);
+                return;
+            }
+            if let Some(old_value) = &*old_value
+                && equals(old_value, &new_value)
+            {
+                return;
+            }
+            old_value.replace(new_value.clone())
+        };
+
+        let this = get_active_sub().expect("BUG: no active sub in the effect of `watch`");
+        let callback = callback.clone();
+        untracked(|| {
+            scheduler(Box::new(move || {
+                if check_handle(this, NodeKind::Effect).is_err() {
+                    return;
+                }
+                untracked(|| callback(&new_value, old_value.as_deref()));
+                if once {
+                    dispose_oper(this, NodeKind::Effect);
+                }
+            }))
+        });
+    })
+}
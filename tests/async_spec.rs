@@ -0,0 +1,159 @@
+mod common;
+
+use alien_signals::{AsyncState, Signal, async_computed, spawn_effect};
+use common::spawner;
+use futures::channel::oneshot;
+use futures::executor::LocalPool;
+
+#[test]
+fn should_expose_pending_ready_and_err() {
+    let mut pool = LocalPool::new();
+    let n = Signal::new(1);
+
+    let checked = async_computed(spawner(&pool), move |_| {
+        let n = n.get();
+        async move { if n > 0 { Ok(n * 10) } else { Err("negative") } }
+    });
+    assert_eq!(checked.get(), AsyncState::Pending);
+
+    pool.run_until_stalled();
+    assert_eq!(checked.get(), AsyncState::Ready(10));
+
+    n.set(-1);
+    assert!(checked.is_pending());
+    pool.run_until_stalled();
+    assert_eq!(checked.get(), AsyncState::Err("negative"));
+}
+
+#[test]
+fn should_cancel_stale_runs() {
+    let mut pool = LocalPool::new();
+    let key = Signal::new(1);
+    let senders = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let alive_futures = std::rc::Rc::new(());
+
+    let value = async_computed(spawner(&pool), {
+        let senders = senders.clone();
+        let alive_futures = alive_futures.clone();
+        move |_| {
+            let key = key.get();
+            let (tx, rx) = oneshot::channel::<i32>();
+            senders.borrow_mut().push(tx);
+            let alive = alive_futures.clone();
+            async move {
+                let _alive = alive;
+                rx.await.map(|v| v + key).map_err(|_| ())
+            }
+        }
+    });
+    pool.run_until_stalled();
+    assert_eq!(std::rc::Rc::strong_count(&alive_futures), 3);
+
+    key.set(100);
+    pool.run_until_stalled();
+    // the first future is dropped
+    assert_eq!(std::rc::Rc::strong_count(&alive_futures), 3);
+
+    let mut senders = senders.borrow_mut().drain(..).collect::<Vec<_>>();
+    let second = senders.pop().unwrap();
+    let first = senders.pop().unwrap();
+    assert!(first.send(1).is_err());
+    second.send(2).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(value.get(), AsyncState::Ready(102));
+}
+
+#[test]
+fn should_pass_previous_ready_value() {
+    let mut pool = LocalPool::new();
+    let step = Signal::new(1);
+
+    let total = async_computed(spawner(&pool), move |prev: Option<i32>| {
+        let step = step.get();
+        async move { Ok::<_, ()>(prev.unwrap_or(0) + step) }
+    });
+    pool.run_until_stalled();
+    step.set(2);
+    pool.run_until_stalled();
+    step.set(3);
+    pool.run_until_stalled();
+    assert_eq!(total.get(), AsyncState::Ready(6));
+}
+
+#[test]
+fn should_not_track_reads_in_future() {
+    let mut pool = LocalPool::new();
+    let tracked = Signal::new(1);
+    let untracked = Signal::new(10);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    let sum = async_computed(spawner(&pool), {
+        let runs = runs.clone();
+        move |_| {
+            runs.set(runs.get() + 1);
+            let tracked = tracked.get();
+            async move { Ok::<_, ()>(tracked + untracked.get()) }
+        }
+    });
+    pool.run_until_stalled();
+    assert_eq!(sum.get(), AsyncState::Ready(11));
+
+    untracked.set(20);
+    pool.run_until_stalled();
+    assert_eq!(runs.get(), 1);
+    assert_eq!(sum.get(), AsyncState::Ready(11));
+}
+
+#[test]
+fn should_cancel_on_dispose() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = oneshot::channel::<i32>();
+    let rx = std::cell::RefCell::new(Some(rx));
+
+    let value = async_computed(spawner(&pool), move |_| {
+        let rx = rx.borrow_mut().take().unwrap();
+        async move { rx.await.map_err(|_| ()) }
+    });
+    pool.run_until_stalled();
+    value.dispose();
+    pool.run_until_stalled();
+    assert!(tx.send(1).is_err());
+}
+
+#[test]
+fn should_spawn_effect_and_cancel_previous_run() {
+    let mut pool = LocalPool::new();
+    let src = Signal::new(0);
+    let logs = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let senders = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+
+    let effect = spawn_effect(spawner(&pool), {
+        let logs = logs.clone();
+        let senders = senders.clone();
+        move || {
+            let value = src.get();
+            let (tx, rx) = oneshot::channel::<()>();
+            senders.borrow_mut().push(tx);
+            let logs = logs.clone();
+            async move {
+                if rx.await.is_ok() {
+                    logs.borrow_mut().push(value);
+                }
+            }
+        }
+    });
+    pool.run_until_stalled();
+    src.set(1);
+    pool.run_until_stalled();
+
+    for tx in senders.borrow_mut().drain(..) {
+        let _ = tx.send(());
+    }
+    pool.run_until_stalled();
+    assert_eq!(*logs.borrow(), [1]);
+
+    effect.dispose();
+    src.set(2);
+    pool.run_until_stalled();
+    assert!(senders.borrow().is_empty());
+}
@@ -0,0 +1,72 @@
+use alien_signals::{BatchGuard, Effect, Signal, batch, get_batch_depth};
+
+#[test]
+fn should_flush_once_after_batch() {
+    let a = Signal::new(1);
+    let b = Signal::new(2);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || observed.lock().unwrap().push(a.get() + b.get())
+    });
+
+    let returned = batch(|| {
+        a.set(10);
+        b.set(20);
+        assert_eq!(*observed.lock().unwrap(), [3]);
+        "returned"
+    });
+    assert_eq!(returned, "returned");
+    assert_eq!(*observed.lock().unwrap(), [3, 30]);
+}
+
+#[test]
+fn should_end_batch_on_guard_drop() {
+    let src = Signal::new(0);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || observed.lock().unwrap().push(src.get())
+    });
+
+    {
+        let _outer = BatchGuard::new();
+        {
+            let _inner = BatchGuard::new();
+            src.set(1);
+        }
+        assert_eq!(get_batch_depth(), 1);
+        src.set(2);
+        assert_eq!(*observed.lock().unwrap(), [0]);
+    }
+    assert_eq!(get_batch_depth(), 0);
+    assert_eq!(*observed.lock().unwrap(), [0, 2]);
+}
+
+#[test]
+fn should_end_batch_on_panic() {
+    let src = Signal::new(0);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || observed.lock().unwrap().push(src.get())
+    });
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        batch(|| {
+            src.set(1);
+            panic!("in batch");
+        })
+    }));
+    assert!(panicked.is_err());
+    assert_eq!(get_batch_depth(), 0);
+    // not flushed while unwinding
+    assert_eq!(*observed.lock().unwrap(), [0]);
+
+    // and still working after the panic
+    src.set(2);
+    assert_eq!(*observed.lock().unwrap(), [0, 2]);
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "causality")]
+
+use alien_signals::inspect::AsNodeId;
+use alien_signals::{Computed, Effect, Signal, batch, trigger};
+
+#[test]
+fn should_record_signal_and_computeds_of_last_run() {
+    let price = Signal::new(10).named("price");
+    let quantity = Signal::new(1).named("quantity");
+    let subtotal = Computed::new(move |_| price.get() * quantity.get()).named("subtotal");
+    let total = Computed::new(move |_| subtotal.get() + 5).named("total");
+    let e = Effect::new(move || {
+        total.get();
+    });
+    assert_eq!(e.last_cause(), None);
+
+    let line = line!() + 1;
+    quantity.set(2);
+    let cause = e.last_cause().unwrap();
+    assert_eq!(cause.signal, quantity.node_id());
+    assert_eq!(cause.via, [subtotal.node_id(), total.node_id()]);
+    let location = cause.location.unwrap();
+    assert_eq!((location.file(), location.line()), (file!(), line));
+    assert_eq!(
+        cause.to_string(),
+        format!(r#""quantity" set at {location} -> "subtotal" -> "total""#)
+    );
+
+    price.set(20);
+    assert_eq!(e.last_cause().unwrap().signal, price.node_id());
+}
+
+#[test]
+fn should_keep_cause_when_not_rerun() {
+    let a = Signal::new(1);
+    let b = Signal::new(1);
+    let parity = Computed::new(move |_| a.get() % 2);
+    let e = Effect::new(move || {
+        parity.get();
+        b.get();
+    });
+
+    b.set(2);
+    assert_eq!(e.last_cause().unwrap().signal, b.node_id());
+    assert!(e.last_cause().unwrap().via.is_empty());
+
+    // `parity` doesn't change, so the effect doesn't run
+    a.set(3);
+    assert_eq!(e.last_cause().unwrap().signal, b.node_id());
+
+    a.set(4);
+    let cause = e.last_cause().unwrap();
+    assert_eq!(
+        (cause.signal, cause.via),
+        (a.node_id(), vec![parity.node_id()])
+    );
+}
+
+#[test]
+fn should_record_first_signal_in_batch() {
+    let a = Signal::new(1);
+    let b = Signal::new(1);
+    let e = Effect::new(move || {
+        a.get();
+        b.get();
+    });
+
+    batch(|| {
+        b.set(2);
+        a.set(2);
+    });
+    assert_eq!(e.last_cause().unwrap().signal, b.node_id());
+}
+
+#[test]
+fn should_record_triggered_and_nested_sets() {
+    let source = Signal::new(0).named("source");
+    let mirror = Signal::new(0).named("mirror");
+    let forward_line = line!() + 1;
+    let _forward = Effect::new(move || mirror.set(source.get()));
+    let e = Effect::new(move || {
+        mirror.get();
+    });
+
+    source.set(1);
+    let cause = e.last_cause().unwrap();
+    assert_eq!(cause.signal, mirror.node_id());
+    // set in the effect `_forward`, not here
+    assert_eq!(cause.location.unwrap().line(), forward_line);
+
+    let line = line!() + 1;
+    trigger(move || {
+        mirror.get();
+    });
+    assert_eq!(e.last_cause().unwrap().location.unwrap().line(), line);
+}
+
+#[test]
+fn should_label_disposed_nodes() {
+    let a = Signal::new(1);
+    let e = Effect::new(move || {
+        a.get();
+    });
+    a.set(2);
+    let cause = e.last_cause().unwrap();
+    e.dispose();
+    a.dispose();
+    assert!(cause.to_string().starts_with("<disposed> set at "));
+}
@@ -0,0 +1,118 @@
+use alien_signals::{Computed, Effect, EffectScope, Signal, on_cleanup};
+
+type Log = std::rc::Rc<std::cell::RefCell<Vec<String>>>;
+
+fn log(log: &Log, entry: impl Into<String>) {
+    log.borrow_mut().push(entry.into());
+}
+
+#[test]
+fn should_run_cleanups_before_rerun_and_on_dispose() {
+    let src = Signal::new(1);
+    let logs = Log::default();
+
+    let effect = Effect::new({
+        let logs = logs.clone();
+        move || {
+            let value = src.get();
+            log(&logs, format!("run {value}"));
+            let logs = logs.clone();
+            on_cleanup(move || log(&logs, format!("cleanup {value}")));
+        }
+    });
+    src.set(2);
+    effect.dispose();
+    src.set(3);
+
+    assert_eq!(*logs.borrow(), ["run 1", "cleanup 1", "run 2", "cleanup 2"]);
+}
+
+#[test]
+fn should_run_cleanups_in_reverse_order() {
+    let logs = Log::default();
+
+    let scope = EffectScope::new({
+        let logs = logs.clone();
+        move || {
+            for i in 0..3 {
+                let logs = logs.clone();
+                on_cleanup(move || log(&logs, format!("cleanup {i}")));
+            }
+        }
+    });
+    assert!(logs.borrow().is_empty());
+
+    scope.dispose();
+    assert_eq!(*logs.borrow(), ["cleanup 2", "cleanup 1", "cleanup 0"]);
+}
+
+#[test]
+fn should_run_cleanups_of_inner_effects_on_scope_dispose() {
+    let src = Signal::new(0);
+    let logs = Log::default();
+
+    let scope = EffectScope::new({
+        let logs = logs.clone();
+        move || {
+            Effect::new({
+                let logs = logs.clone();
+                move || {
+                    let _ = src.get();
+                    let logs = logs.clone();
+                    on_cleanup(move || log(&logs, "effect"));
+                }
+            });
+            let logs = logs.clone();
+            on_cleanup(move || log(&logs, "scope"));
+        }
+    });
+    scope.dispose();
+    assert_eq!(*logs.borrow(), ["effect", "scope"]);
+}
+
+#[test]
+fn should_run_cleanups_before_recomputing() {
+    let src = Signal::new(1);
+    let logs = Log::default();
+
+    let c = Computed::new({
+        let logs = logs.clone();
+        move |_| {
+            let value = src.get();
+            let logs = logs.clone();
+            on_cleanup(move || log(&logs, format!("cleanup {value}")));
+            value * 2
+        }
+    });
+    assert_eq!(c.get(), 2);
+    src.set(2);
+    assert!(logs.borrow().is_empty());
+    assert_eq!(c.get(), 4);
+    assert_eq!(*logs.borrow(), ["cleanup 1"]);
+
+    c.dispose();
+    assert_eq!(*logs.borrow(), ["cleanup 1", "cleanup 2"]);
+}
+
+#[test]
+fn should_not_track_reads_in_cleanups() {
+    let src = Signal::new(0);
+    let read_in_cleanup = Signal::new(0);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    Effect::new({
+        let runs = runs.clone();
+        move || {
+            let _ = src.get();
+            runs.set(runs.get() + 1);
+            on_cleanup(move || {
+                let _ = read_in_cleanup.get();
+            });
+        }
+    });
+    src.set(1);
+    assert_eq!(runs.get(), 2);
+
+    read_in_cleanup.set(1);
+    assert_eq!(runs.get(), 2);
+}
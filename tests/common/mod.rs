@@ -0,0 +1,8 @@
+use alien_signals::SpawnedFuture;
+use futures::executor::LocalPool;
+use futures::task::LocalSpawnExt;
+
+pub fn spawner(pool: &LocalPool) -> impl Fn(SpawnedFuture) + 'static {
+    let spawner = pool.spawner();
+    move |future| spawner.spawn_local(future).unwrap()
+}
@@ -1,3 +1,4 @@
+use alien_signals::inspect::{AsNodeId, deps_of};
 use alien_signals::{Computed, Signal};
 
 #[test]
@@ -63,3 +64,69 @@ fn should_not_update_if_the_signal_value_is_reverted() {
     let _ = c1.get();
     assert_eq!(*times.lock().unwrap(), 1);
 }
+
+#[test]
+#[should_panic(expected = "this `Computed` is read while computing itself: total -> tax -> total")]
+fn should_panic_on_cycle_with_labelled_path() {
+    let price = Signal::new(100);
+    let total: std::rc::Rc<std::cell::Cell<Option<Computed<i32>>>> = Default::default();
+    let tax = Computed::new({
+        let total = total.clone();
+        move |_| total.get().map_or(0, |total| total.get() / 10)
+    })
+    .named("tax");
+    total.set(Some(
+        Computed::new(move |_| price.get() + tax.get()).named("total"),
+    ));
+    total.get().unwrap().get();
+}
+
+#[test]
+fn should_return_cycle_error_from_try_get() {
+    let this: std::rc::Rc<std::cell::Cell<Option<Computed<i32>>>> = Default::default();
+    let error = std::rc::Rc::new(std::cell::RefCell::new(None));
+    let c = Computed::new({
+        let (this, error) = (this.clone(), error.clone());
+        move |_| match this.get().unwrap().try_get() {
+            Ok(value) => value,
+            Err(e) => {
+                *error.borrow_mut() = Some(e);
+                0
+            }
+        }
+    });
+    this.set(Some(c));
+
+    assert_eq!(c.get(), 0);
+    let Some(alien_signals::Error::Cycle(path)) = error.borrow_mut().take() else {
+        panic!("expected `Error::Cycle`");
+    };
+    assert_eq!(path.len(), 2);
+    assert!(path.iter().all(|label| label.starts_with("Computed@0x")));
+}
+
+#[test]
+fn should_restore_active_sub_when_getter_panics() {
+    let fail = Signal::new(true);
+    let other = Signal::new(0);
+    let c = Computed::new(move |_| {
+        if fail.get() {
+            panic!("failed");
+        }
+        1
+    });
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| c.get())).is_err());
+    assert!(alien_signals::get_active_sub().is_none());
+
+    other.get();
+    assert_eq!(deps_of(&c), [fail.node_id()]);
+
+    fail.set(false);
+    assert_eq!(c.get(), 1);
+
+    fail.set(true);
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| c.get())).is_err());
+    assert!(alien_signals::get_active_sub().is_none());
+    fail.set(false);
+    assert_eq!(c.get(), 1);
+}
@@ -0,0 +1,115 @@
+use alien_signals::inspect::{Format, export_graph, name_of};
+use alien_signals::{Computed, Effect, EffectScope, Runtime, Signal};
+
+#[test]
+fn should_show_name_flags_and_value() {
+    let price = Signal::new(3).named("price");
+    let total = Computed::new(move |_| price.get() * 2).named("cart_total");
+    assert_eq!(
+        format!("{price:?}"),
+        r#"Signal { name: "price", flags: Flags(MUTABLE), value: 3 }"#
+    );
+    // not computed yet
+    assert_eq!(
+        format!("{total:?}"),
+        r#"Computed { name: "cart_total", flags: Flags(NONE) }"#
+    );
+
+    let e = Effect::new(move || {
+        total.get();
+    })
+    .named("render");
+    assert_eq!(
+        format!("{total:?}"),
+        r#"Computed { name: "cart_total", flags: Flags(MUTABLE), value: 6 }"#
+    );
+    assert_eq!(
+        format!("{e:?}"),
+        r#"Effect { name: "render", flags: Flags(WATCHING) }"#
+    );
+
+    // the latest value, even before it's propagated
+    alien_signals::batch(|| {
+        price.set(4);
+        assert_eq!(
+            format!("{price:?}"),
+            r#"Signal { name: "price", flags: Flags(MUTABLE | DIRTY), value: 4 }"#
+        );
+    });
+}
+
+#[test]
+fn should_show_unnamed_and_disposed_handles() {
+    let count = Signal::new(0);
+    let scope = EffectScope::new(|| {});
+    assert_eq!(
+        format!("{count:?}"),
+        "Signal { flags: Flags(MUTABLE), value: 0 }"
+    );
+    assert_eq!(format!("{scope:?}"), "EffectScope { flags: Flags(NONE) }");
+
+    let handle = Effect::new(|| {}).named("detached").into_handle();
+    assert_eq!(
+        format!("{handle:?}"),
+        r#"EffectHandle { name: "detached", flags: Flags(WATCHING), kind: Effect }"#
+    );
+
+    count.dispose();
+    assert_eq!(format!("{count:?}"), "Signal { error: Disposed(Signal) }");
+
+    let runtime = Runtime::new();
+    let other = runtime.enter(|| Signal::new(0));
+    assert_eq!(
+        format!("{other:?}"),
+        "Signal { error: WrongRuntime(Signal) }"
+    );
+}
+
+#[test]
+fn should_expose_names_for_inspection() {
+    let a = Signal::new(1).named("a");
+    let b = Signal::new(2);
+    assert_eq!(name_of(&a), Some("a".into()));
+    assert_eq!(name_of(&b), None);
+
+    let owned = String::from("renamed");
+    let a = a.named(owned);
+    assert_eq!(name_of(&a), Some("renamed".into()));
+}
+
+#[test]
+#[should_panic(expected = r#"this `Signal` "cart" can't be updated while it's being read"#)]
+fn should_include_name_in_panic_message() {
+    let cart = Signal::new(vec![1]).named("cart");
+    cart.with(|_| cart.set(vec![]));
+}
+
+#[test]
+fn should_label_exported_graph() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let a = Signal::new(1).named("say \"hi\"");
+        let c = Computed::new(move |_| a.get()).named("c");
+        assert_eq!(c.get(), 1);
+
+        assert_eq!(
+            export_graph(Format::Dot),
+            "\
+digraph {
+    n0 [label=\"say \\\"hi\\\"\\nSignal #0\\nMUTABLE\", shape=ellipse];
+    n1 [label=\"c\\nComputed #1\\nMUTABLE\", shape=box];
+    n0 -> n1 [label=\"v0\"];
+}
+"
+        );
+        assert_eq!(
+            export_graph(Format::Json),
+            concat!(
+                r#"{"nodes":["#,
+                r#"{"id":0,"kind":"Signal","label":"say \"hi\"","flags":["MUTABLE"]},"#,
+                r#"{"id":1,"kind":"Computed","label":"c","flags":["MUTABLE"]}"#,
+                r#"],"links":[{"dep":0,"sub":1,"version":0}]}"#,
+            )
+        );
+    });
+}
@@ -0,0 +1,167 @@
+use alien_signals::{Computed, Effect, EffectScope, Signal};
+
+#[test]
+fn should_drop_captured_values_on_dispose() {
+    let src = Signal::new(0);
+    let captured = std::rc::Rc::new(());
+
+    let effect = Effect::new({
+        let captured = captured.clone();
+        move || {
+            let _ = &captured;
+            let _ = src.get();
+        }
+    });
+    assert_eq!(std::rc::Rc::strong_count(&captured), 2);
+
+    effect.dispose();
+    assert_eq!(std::rc::Rc::strong_count(&captured), 1);
+}
+
+#[test]
+fn should_keep_working_while_reusing_disposed_slots() {
+    let src = Signal::new(0);
+    let c = Computed::new(move |_| src.get() * 2);
+
+    let triggers = std::rc::Rc::new(std::sync::Mutex::new(0));
+
+    for _ in 0..10_000 {
+        let effect = Effect::new({
+            let triggers = triggers.clone();
+            move || {
+                let _ = c.get();
+                *triggers.lock().unwrap() += 1;
+            }
+        });
+        src.set(src.get() + 1);
+        effect.dispose();
+    }
+    assert_eq!(*triggers.lock().unwrap(), 20_000);
+    assert_eq!(std::rc::Rc::strong_count(&triggers), 1);
+
+    let scope = EffectScope::new({
+        let triggers = triggers.clone();
+        move || {
+            Effect::new(move || {
+                let _ = c.get();
+                *triggers.lock().unwrap() += 1;
+            });
+        }
+    });
+    assert_eq!(*triggers.lock().unwrap(), 20_001);
+    src.set(src.get() + 1);
+    assert_eq!(*triggers.lock().unwrap(), 20_002);
+    scope.dispose();
+    src.set(src.get() + 1);
+    assert_eq!(*triggers.lock().unwrap(), 20_002);
+}
+
+#[test]
+fn should_allow_disposing_effect_in_its_own_run() {
+    let src = Signal::new(0);
+
+    let triggers = std::rc::Rc::new(std::sync::Mutex::new(0));
+    let this: std::rc::Rc<std::sync::Mutex<Option<Effect>>> =
+        std::rc::Rc::new(std::sync::Mutex::new(None));
+
+    let effect = Effect::new({
+        let triggers = triggers.clone();
+        let this = this.clone();
+        move || {
+            *triggers.lock().unwrap() += 1;
+            if src.get() == 1 {
+                if let Some(this) = this.lock().unwrap().take() {
+                    this.dispose();
+                }
+            }
+        }
+    });
+    this.lock().unwrap().replace(effect);
+    assert_eq!(*triggers.lock().unwrap(), 1);
+
+    src.set(1);
+    assert_eq!(*triggers.lock().unwrap(), 2);
+    src.set(2);
+    assert_eq!(*triggers.lock().unwrap(), 2);
+}
+
+#[test]
+fn should_detect_use_of_disposed_signal_and_computed() {
+    let src = Signal::new(1);
+    let c = Computed::new(move |_| src.get() * 2);
+    assert_eq!(c.try_get(), Ok(2));
+
+    c.dispose();
+    assert_eq!(
+        c.try_get(),
+        Err(alien_signals::Error::Disposed(
+            alien_signals::NodeKind::Computed
+        ))
+    );
+
+    // slot of `c` is reused here
+    let other = Signal::new(String::from("other"));
+    src.dispose();
+    assert_eq!(
+        src.try_get(),
+        Err(alien_signals::Error::Disposed(
+            alien_signals::NodeKind::Signal
+        ))
+    );
+    assert_eq!(other.get(), "other");
+}
+
+#[test]
+#[should_panic(expected = "this `Signal` has already been disposed")]
+fn should_panic_on_setting_disposed_signal() {
+    let src = Signal::new(1);
+    src.dispose();
+    src.set(2);
+}
+
+#[test]
+#[should_panic(expected = "use of a `Node` whose slot is already freed")]
+fn should_panic_on_stale_node() {
+    let active_sub = std::rc::Rc::new(std::sync::Mutex::new(None));
+    let effect = Effect::new({
+        let active_sub = active_sub.clone();
+        move || {
+            *active_sub.lock().unwrap() = alien_signals::get_active_sub();
+        }
+    });
+    effect.dispose();
+
+    let _ = active_sub.lock().unwrap().unwrap().flags();
+}
+
+#[test]
+fn should_free_inner_effects_disposed_by_outer_rerun() {
+    let src = Signal::new(0);
+    let captured = std::rc::Rc::new(());
+    let inner_effects = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+
+    let outer = Effect::new({
+        let captured = captured.clone();
+        let inner_effects = inner_effects.clone();
+        move || {
+            let _ = src.get();
+            let captured = captured.clone();
+            inner_effects.lock().unwrap().push(Effect::new(move || {
+                let _ = &captured;
+            }));
+        }
+    });
+    assert_eq!(std::rc::Rc::strong_count(&captured), 3);
+
+    src.set(1);
+    src.set(2);
+    // only the latest inner effect is alive
+    assert_eq!(std::rc::Rc::strong_count(&captured), 3);
+
+    // disposing automatically disposed ones is no-op
+    for inner in inner_effects.lock().unwrap().drain(..) {
+        inner.dispose();
+    }
+    outer.dispose();
+    assert_eq!(std::rc::Rc::strong_count(&captured), 1);
+}
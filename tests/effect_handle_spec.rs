@@ -0,0 +1,111 @@
+use alien_signals::{Effect, EffectScope, Runtime, Signal};
+
+fn counting_effect(src: Signal<i32>, triggers: &std::rc::Rc<std::cell::Cell<usize>>) -> Effect {
+    let triggers = triggers.clone();
+    Effect::new(move || {
+        let _ = src.get();
+        triggers.set(triggers.get() + 1);
+    })
+}
+
+#[test]
+fn should_dispose_effect_on_drop() {
+    let src = Signal::new(0);
+    let triggers = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    let handle = counting_effect(src, &triggers).into_handle();
+    src.set(1);
+    assert_eq!(triggers.get(), 2);
+
+    drop(handle);
+    src.set(2);
+    assert_eq!(triggers.get(), 2);
+    assert_eq!(std::rc::Rc::strong_count(&triggers), 1);
+}
+
+#[test]
+fn should_keep_detached_effect_alive() {
+    let src = Signal::new(0);
+    let triggers = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    counting_effect(src, &triggers).into_handle().detach();
+    src.set(1);
+    assert_eq!(triggers.get(), 2);
+}
+
+#[test]
+fn should_dispose_scope_on_drop() {
+    let src = Signal::new(0);
+    let triggers = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    let handle = EffectScope::new({
+        let triggers = triggers.clone();
+        move || {
+            counting_effect(src, &triggers);
+            counting_effect(src, &triggers);
+        }
+    })
+    .into_handle();
+    assert_eq!(triggers.get(), 2);
+
+    drop(handle);
+    src.set(1);
+    assert_eq!(triggers.get(), 2);
+}
+
+#[test]
+fn should_not_panic_on_dropping_already_disposed_effect() {
+    let src = Signal::new(0);
+    let triggers = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    // the inner effect is disposed by the re-run of the outer one
+    let inner_handles = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let outer = Effect::new({
+        let triggers = triggers.clone();
+        let inner_handles = inner_handles.clone();
+        move || {
+            let _ = src.get();
+            let inner = Effect::new({
+                let triggers = triggers.clone();
+                move || triggers.set(triggers.get() + 1)
+            });
+            inner_handles.borrow_mut().push(inner.into_handle());
+        }
+    });
+    src.set(1);
+    assert_eq!(triggers.get(), 2);
+
+    inner_handles.borrow_mut().clear();
+    outer.dispose();
+}
+
+#[test]
+fn should_not_panic_on_dropping_out_of_runtime() {
+    let runtime = Runtime::new();
+    let handle = runtime.enter(|| Effect::new(|| {}).into_handle());
+    // out of `runtime`
+    drop(handle);
+
+    let handle = runtime.enter(|| Effect::new(|| {}).into_handle());
+    drop(runtime);
+    drop(handle);
+}
+
+#[test]
+fn should_not_panic_on_dropping_on_thread_exit() {
+    thread_local! {
+        static HANDLE: std::cell::RefCell<Option<alien_signals::EffectHandle>> =
+            const { std::cell::RefCell::new(None) };
+    }
+
+    std::thread::spawn(|| {
+        let src = Signal::new(0);
+        let handle = Effect::new(move || {
+            let _ = src.get();
+        })
+        .into_handle();
+        HANDLE.with(|h| *h.borrow_mut() = Some(handle));
+    })
+    .join()
+    .unwrap();
+}
@@ -1,3 +1,4 @@
+use alien_signals::inspect::{AsNodeId, deps_of};
 use alien_signals::{Computed, Effect, EffectScope, Signal};
 
 #[test]
@@ -361,3 +362,79 @@ fn should_support_custom_recurse_effect() {
 
     assert_eq!(*triggers.lock().unwrap(), 6);
 }
+
+#[test]
+fn should_pass_previous_state_to_effect() {
+    let src = Signal::new(1);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    let effect = Effect::new_with_state(Vec::<i32>::new(), {
+        let observed = observed.clone();
+        move |mut history| {
+            history.push(src.get());
+            observed.lock().unwrap().push(history.clone());
+            history
+        }
+    });
+
+    src.set(2);
+    src.set(3);
+    assert_eq!(
+        *observed.lock().unwrap(),
+        [vec![1], vec![1, 2], vec![1, 2, 3]]
+    );
+
+    effect.dispose();
+    src.set(4);
+    assert_eq!(observed.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn should_rerun_effect_after_panic() {
+    let src = Signal::new(1);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+    let effect = Effect::new({
+        let runs = runs.clone();
+        move || {
+            runs.set(runs.get() + 1);
+            if src.get() == 2 {
+                panic!("failed");
+            }
+        }
+    });
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| src.set(2)));
+    assert!(panicked.is_err());
+    assert_eq!(runs.get(), 2);
+    assert!(alien_signals::get_active_sub().is_none());
+
+    // not linked to the effect
+    let other = Signal::new(0);
+    other.get();
+    assert_eq!(deps_of(&effect), [src.node_id()]);
+
+    src.set(3);
+    assert_eq!(runs.get(), 3);
+    effect.dispose();
+}
+
+#[test]
+fn should_panic_on_rerun_of_effect_with_state_lost_by_panic() {
+    let src = Signal::new(1);
+    let effect = Effect::new_with_state(0, move |state| {
+        if src.get() == 2 {
+            panic!("failed");
+        }
+        state + 1
+    });
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| src.set(2)));
+    assert_eq!(*panicked.unwrap_err().downcast::<&str>().unwrap(), "failed");
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| src.set(3)));
+    assert_eq!(
+        *panicked.unwrap_err().downcast::<String>().unwrap(),
+        "the state of this effect is lost by a panic in its previous run"
+    );
+    effect.dispose();
+}
@@ -0,0 +1,259 @@
+use alien_signals::inspect::{
+    AsNodeId, Format, NodeId, deps_of, export_graph, flags_of, is_alive, kind_of, subscribers_of,
+    walk,
+};
+use alien_signals::{Computed, Effect, EffectScope, Flags, NodeKind, Runtime, Signal};
+
+#[test]
+fn should_list_deps_and_subscribers() {
+    let a = Signal::new(1);
+    let b = Signal::new(2);
+    let sum = Computed::new(move |_| a.get() + b.get());
+    let e = Effect::new(move || {
+        sum.get();
+        a.get();
+    });
+
+    assert_eq!(deps_of(&sum), [a.node_id(), b.node_id()]);
+    assert_eq!(deps_of(&e), [sum.node_id(), a.node_id()]);
+    assert_eq!(subscribers_of(&a), [sum.node_id(), e.node_id()]);
+    assert_eq!(subscribers_of(&sum), [e.node_id()]);
+    assert!(deps_of(&a).is_empty());
+    assert!(subscribers_of(&e).is_empty());
+}
+
+#[test]
+fn should_follow_dynamic_deps() {
+    let flag = Signal::new(true);
+    let a = Signal::new(1);
+    let b = Signal::new(2);
+    let c = Computed::new(move |_| if flag.get() { a.get() } else { b.get() });
+    let _e = Effect::new(move || {
+        c.get();
+    });
+    assert_eq!(deps_of(&c), [flag.node_id(), a.node_id()]);
+
+    flag.set(false);
+    assert_eq!(deps_of(&c), [flag.node_id(), b.node_id()]);
+    assert!(subscribers_of(&a).is_empty());
+}
+
+#[test]
+fn should_report_kinds_and_flags() {
+    let a = Signal::new(1);
+    let c = Computed::new(move |_| a.get());
+    let e = Effect::new(move || {
+        c.get();
+    });
+    let scope = EffectScope::new(|| {});
+
+    assert_eq!(kind_of(&a), NodeKind::Signal);
+    assert_eq!(kind_of(&c), NodeKind::Computed);
+    assert_eq!(kind_of(&e), NodeKind::Effect);
+    assert_eq!(kind_of(&scope), NodeKind::EffectScope);
+
+    assert!(flags_of(&a).contains(Flags::MUTABLE));
+    assert!(flags_of(&e).contains(Flags::WATCHING));
+    assert!(!flags_of(&e).contains(Flags::DIRTY));
+    assert_eq!(format!("{:?}", flags_of(&e)), "Flags(WATCHING)");
+
+    alien_signals::batch(|| {
+        a.set(2);
+        assert!(flags_of(&c).contains(Flags::PENDING));
+    });
+    assert!(!flags_of(&c).contains(Flags::PENDING));
+    assert_eq!(format!("{:?}", Flags::NONE), "Flags(NONE)");
+}
+
+#[test]
+fn should_include_owned_effects_in_deps() {
+    let inner = std::rc::Rc::new(std::cell::Cell::new(None::<NodeId>));
+    let scope = EffectScope::new({
+        let inner = inner.clone();
+        move || {
+            let e = Effect::new(|| {});
+            inner.set(Some(e.node_id()));
+        }
+    });
+    let inner = inner.get().unwrap();
+    assert_eq!(deps_of(&scope), [inner]);
+    assert_eq!(subscribers_of(&inner), [scope.node_id()]);
+}
+
+#[test]
+fn should_not_track_or_recompute() {
+    let a = Signal::new(1);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+    let c = Computed::new({
+        let runs = runs.clone();
+        move |_| {
+            runs.set(runs.get() + 1);
+            a.get()
+        }
+    });
+    let e = Effect::new(move || {
+        deps_of(&c);
+        subscribers_of(&a);
+    });
+    assert_eq!(runs.get(), 0);
+    assert!(deps_of(&e).is_empty());
+}
+
+#[test]
+fn should_detect_disposed_nodes() {
+    let a = Signal::new(1);
+    let c = Computed::new(move |_| a.get());
+    let _e = Effect::new(move || {
+        c.get();
+    });
+    let id = c.node_id();
+    assert!(is_alive(&c));
+    assert!(!id.is_disposed());
+
+    c.dispose();
+    assert!(!is_alive(&id));
+    assert!(id.is_disposed());
+    assert!(subscribers_of(&a).is_empty());
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| id.deps())).is_err());
+}
+
+#[test]
+fn should_walk_all_live_nodes() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+        let c = Computed::new(move |_| a.get() + b.get());
+        let e = Effect::new(move || {
+            c.get();
+        });
+        b.dispose();
+
+        let mut visited = Vec::new();
+        walk(|node| visited.push(node));
+        assert_eq!(visited.len(), 3);
+        for node in [a.node_id(), c.node_id(), e.node_id()] {
+            assert!(visited.contains(&node));
+        }
+
+        // edges of the whole graph
+        let mut edges = Vec::new();
+        walk(|node| {
+            for dep in node.deps() {
+                edges.push((dep.kind(), node.kind()));
+            }
+        });
+        edges.sort_by_key(|(dep, sub)| (*dep as u8, *sub as u8));
+        assert_eq!(
+            edges,
+            [
+                (NodeKind::Signal, NodeKind::Computed),
+                (NodeKind::Computed, NodeKind::Effect)
+            ]
+        );
+    });
+
+    // other runtimes are not visited
+    let mut count = 0;
+    walk(|_| count += 1);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn should_skip_nodes_disposed_while_walking() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+
+        let mut visited = Vec::new();
+        walk(|node| {
+            visited.push(node);
+            if node == a.node_id() {
+                b.dispose();
+            }
+        });
+        assert_eq!(visited, [a.node_id()]);
+    });
+}
+
+#[test]
+fn should_export_graph_as_dot() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let a = Signal::new(1);
+        let c = Computed::new(move |_| a.get() * 2);
+        let _e = Effect::new(move || {
+            c.get();
+        });
+        alien_signals::batch(|| {
+            a.set(2);
+            assert_eq!(
+                export_graph(Format::Dot),
+                "\
+digraph {
+    n0 [label=\"Signal #0\\nMUTABLE | DIRTY\", shape=ellipse, color=red];
+    n1 [label=\"Computed #1\\nMUTABLE | PENDING\", shape=box, color=orange];
+    n2 [label=\"Effect #2\\nPENDING\", shape=hexagon, color=orange];
+    n0 -> n1 [label=\"v0\"];
+    n1 -> n2 [label=\"v0\"];
+}
+"
+            );
+        });
+    });
+}
+
+#[test]
+fn should_export_graph_as_json() {
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+        let c = Computed::new(move |_| a.get() + b.get());
+        let _e = Effect::new(move || {
+            c.get();
+        });
+        a.set(2); // re-runs `c` and the effect
+        assert_eq!(
+            export_graph(Format::Json),
+            concat!(
+                r#"{"nodes":["#,
+                r#"{"id":0,"kind":"Signal","label":null,"flags":["MUTABLE"]},"#,
+                r#"{"id":1,"kind":"Signal","label":null,"flags":["MUTABLE"]},"#,
+                r#"{"id":2,"kind":"Computed","label":null,"flags":["MUTABLE"]},"#,
+                r#"{"id":3,"kind":"Effect","label":null,"flags":["WATCHING"]}"#,
+                r#"],"links":["#,
+                r#"{"dep":0,"sub":2,"version":1},"#,
+                r#"{"dep":1,"sub":2,"version":1},"#,
+                r#"{"dep":2,"sub":3,"version":2}"#,
+                r#"]}"#,
+            )
+        );
+    });
+
+    let empty = Runtime::new();
+    assert_eq!(
+        empty.enter(|| export_graph(Format::Json)),
+        r#"{"nodes":[],"links":[]}"#
+    );
+}
+
+#[test]
+fn should_tell_trigger_from_effect_scope() {
+    let count = Signal::new(0);
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(None));
+    alien_signals::trigger({
+        let seen = seen.clone();
+        move || {
+            count.get();
+            let sub = alien_signals::get_active_sub().unwrap();
+            *seen.borrow_mut() = Some((sub.node_id().kind(), export_graph(Format::Dot)));
+        }
+    });
+
+    let (kind, dot) = seen.borrow_mut().take().unwrap();
+    assert_eq!(kind, NodeKind::Trigger);
+    assert!(dot.contains("Trigger #1\\nWATCHING\", shape=diamond"));
+    assert!(!dot.contains("EffectScope"));
+}
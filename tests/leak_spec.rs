@@ -0,0 +1,157 @@
+#![cfg(feature = "leak-detector")]
+
+use alien_signals::inspect::AsNodeId;
+use alien_signals::{
+    Computed, Effect, EffectScope, NodeKind, Resource, Runtime, Signal, SpawnedFuture,
+    WatchOptions, async_computed, effect, report_leaks, spawn_effect, watch,
+};
+
+#[test]
+fn should_report_undisposed_nodes_with_creation_sites() {
+    let count = Signal::new(0);
+    let line = line!() + 1;
+    let double = Computed::new(move |_| count.get() * 2).named("double");
+    let e = effect(move || {
+        double.get();
+    });
+
+    let leaks = report_leaks();
+    assert_eq!(leaks.len(), 2);
+    let leak = leaks
+        .iter()
+        .find(|leak| leak.node == double.node_id())
+        .unwrap();
+    assert_eq!(leak.kind, NodeKind::Computed);
+    assert_eq!(leak.name.as_deref(), Some("double"));
+    assert_eq!(
+        (leak.created_at.file(), leak.created_at.line()),
+        (file!(), line)
+    );
+    assert_eq!(
+        leak.to_string(),
+        format!("`Computed` \"double\" created at {}", leak.created_at)
+    );
+    let leak = leaks.iter().find(|leak| leak.node == e.node_id()).unwrap();
+    assert_eq!(leak.created_at.line(), line + 1);
+
+    e.dispose();
+    double.dispose();
+    assert!(report_leaks().is_empty());
+}
+
+#[test]
+fn should_not_report_unobserved_computeds() {
+    let count = Signal::new(0);
+    let double = Computed::new(move |_| count.get() * 2);
+    assert_eq!(double.get(), 0);
+    assert!(report_leaks().is_empty());
+
+    let e = effect(move || {
+        double.get();
+    });
+    let mut leaks = report_leaks()
+        .into_iter()
+        .map(|leak| leak.node)
+        .collect::<Vec<_>>();
+    leaks.sort_by_key(|node| *node != e.node_id());
+    assert_eq!(leaks, [e.node_id(), double.node_id()]);
+
+    e.dispose();
+    assert!(report_leaks().is_empty());
+    double.dispose();
+}
+
+#[test]
+fn should_report_only_owners_of_effects() {
+    let count = Signal::new(0);
+    let scope = EffectScope::new(move || {
+        Effect::new(move || {
+            Effect::new(move || {
+                count.get();
+            });
+        });
+    });
+
+    let leaks = report_leaks();
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(
+        (leaks[0].node, leaks[0].kind),
+        (scope.node_id(), NodeKind::EffectScope)
+    );
+
+    scope.dispose();
+    assert!(report_leaks().is_empty());
+}
+
+#[test]
+fn should_report_leaks_of_current_runtime() {
+    let runtime = Runtime::new();
+    let e = runtime.enter(|| Effect::new(|| ()).named("in runtime"));
+
+    assert!(report_leaks().is_empty());
+    let leaks = runtime.enter(report_leaks);
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(leaks[0].name.as_deref(), Some("in runtime"));
+
+    runtime.enter(|| e.dispose());
+    assert!(runtime.enter(report_leaks).is_empty());
+}
+
+#[test]
+fn should_report_creation_sites_in_user_code_for_helpers() {
+    let count = Signal::new(0);
+    let spawn = |_: SpawnedFuture| {};
+
+    let watch_line = line!() + 1;
+    let watcher = watch(move || count.get(), |_, _| (), WatchOptions::default());
+    let spawned_line = line!() + 1;
+    let spawned = spawn_effect(spawn, move || {
+        count.get();
+        async {}
+    });
+    let async_line = line!() + 1;
+    let value = async_computed(spawn, move |_| {
+        let count = count.get();
+        async move { Ok::<_, ()>(count) }
+    });
+    let resource_line = line!() + 1;
+    let resource = Resource::new(
+        spawn,
+        move || count.get(),
+        |count| async move { Ok::<_, ()>(count) },
+    );
+
+    let mut lines = report_leaks()
+        .iter()
+        .map(|leak| {
+            assert_eq!(leak.created_at.file(), file!());
+            leak.created_at.line()
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+    assert_eq!(lines, [watch_line, spawned_line, async_line, resource_line]);
+
+    watcher.dispose();
+    spawned.dispose();
+    value.dispose();
+    resource.dispose();
+    assert!(report_leaks().is_empty());
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn should_report_creation_sites_of_streams_in_user_code() {
+    let count = Signal::new(0);
+    let line = line!() + 1;
+    let stream = count.to_stream();
+
+    let leaks = report_leaks();
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(
+        (leaks[0].created_at.file(), leaks[0].created_at.line()),
+        (file!(), line)
+    );
+
+    drop(stream);
+    assert!(report_leaks().is_empty());
+}
@@ -0,0 +1,64 @@
+use alien_signals::{Computed, Effect, Signal};
+
+/// neither `Clone` nor `Copy`
+#[derive(Debug, PartialEq)]
+struct Buffer(Vec<u8>);
+
+#[test]
+fn should_store_non_clone_values() {
+    let buf = Signal::new(Buffer(vec![1, 2]));
+    let len = Computed::new(move |_| buf.with(|buf| buf.0.len()));
+    assert_eq!(len.get(), 2);
+
+    buf.set(Buffer(vec![1, 2, 3]));
+    assert_eq!(len.get(), 3);
+    buf.set_with(|buf| Buffer(buf.0.iter().map(|b| b * 2).collect()));
+    assert!(buf.with(|buf| buf.0 == [2, 4, 6]));
+}
+
+#[test]
+fn should_compute_non_clone_values() {
+    let n = Signal::new(2);
+    let buf = Computed::new(move |_| Buffer(vec![0; n.get()]));
+    assert_eq!(buf.with(|buf| buf.0.len()), 2);
+
+    n.set(5);
+    assert_eq!(buf.with(|buf| buf.0.len()), 5);
+}
+
+#[test]
+fn should_notify_set_mut_without_comparing() {
+    let buf = Signal::new(Buffer(vec![]));
+    let triggers = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    Effect::new({
+        let triggers = triggers.clone();
+        move || {
+            buf.with(|_| ());
+            triggers.set(triggers.get() + 1);
+        }
+    });
+    assert_eq!(triggers.get(), 1);
+
+    buf.set_mut(|buf| buf.0.push(1));
+    assert_eq!(triggers.get(), 2);
+
+    // notified even if nothing is changed actually
+    buf.set_mut(|_| ());
+    assert_eq!(triggers.get(), 3);
+
+    // compared by `PartialEq` on `set`
+    buf.set(Buffer(vec![1]));
+    assert_eq!(triggers.get(), 3);
+}
+
+#[test]
+fn should_mutate_latest_pending_value_in_place() {
+    let src = Signal::new(Buffer(vec![]));
+    alien_signals::start_batch();
+    src.set(Buffer(vec![1]));
+    src.set_mut(|buf| buf.0.push(2));
+    src.set_with(|buf| Buffer([&buf.0[..], &[3]].concat()));
+    alien_signals::end_batch();
+    assert!(src.with(|buf| buf.0 == [1, 2, 3]));
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use alien_signals::{Effect, Resource, Signal};
+use common::spawner;
+use futures::channel::oneshot;
+use futures::executor::LocalPool;
+
+type Responders =
+    std::rc::Rc<std::cell::RefCell<Vec<(i32, oneshot::Sender<Result<String, String>>)>>>;
+
+/// fetcher responding when the test sends to the channel
+fn manual_fetcher(
+    responders: &Responders,
+) -> impl Fn(i32) -> futures::future::BoxFuture<'static, Result<String, String>> + 'static {
+    let responders = responders.clone();
+    move |key| {
+        let (tx, rx) = oneshot::channel();
+        responders.borrow_mut().push((key, tx));
+        Box::pin(async move { rx.await.unwrap_or_else(|_| Err("canceled".into())) })
+    }
+}
+
+#[test]
+fn should_refetch_on_key_change() {
+    let mut pool = LocalPool::new();
+    let key = Signal::new(1);
+    let responders = Responders::default();
+
+    let resource = Resource::new(
+        spawner(&pool),
+        move || key.get(),
+        manual_fetcher(&responders),
+    );
+    assert!(resource.loading());
+    assert_eq!(resource.latest(), None);
+
+    let (k, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Ok(format!("value {k}"))).unwrap();
+    pool.run_until_stalled();
+    assert!(!resource.loading());
+    assert_eq!(resource.latest(), Some("value 1".into()));
+
+    key.set(2);
+    assert!(resource.loading());
+    assert_eq!(responders.borrow()[0].0, 2);
+}
+
+#[test]
+fn should_not_refetch_for_equal_key() {
+    let mut pool = LocalPool::new();
+    let user = Signal::new((1, "a"));
+    let responders = Responders::default();
+
+    let resource = Resource::new(
+        spawner(&pool),
+        move || user.get().0,
+        manual_fetcher(&responders),
+    );
+    user.set((1, "b"));
+    assert_eq!(responders.borrow().len(), 1);
+
+    // the running fetch is not cancelled
+    let (k, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Ok(format!("value {k}"))).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(resource.latest(), Some("value 1".into()));
+
+    user.set((2, "b"));
+    assert_eq!(responders.borrow()[0].0, 2);
+    resource.dispose();
+}
+
+#[test]
+fn should_drop_out_of_order_responses() {
+    let mut pool = LocalPool::new();
+    let key = Signal::new(1);
+    let responders = Responders::default();
+
+    let resource = Resource::new(
+        spawner(&pool),
+        move || key.get(),
+        manual_fetcher(&responders),
+    );
+    pool.run_until_stalled();
+    key.set(2);
+    pool.run_until_stalled();
+
+    let mut responders = responders.borrow_mut().drain(..).collect::<Vec<_>>();
+    let (_, second) = responders.pop().unwrap();
+    let (_, first) = responders.pop().unwrap();
+
+    second.send(Ok("second".into())).unwrap();
+    pool.run_until_stalled();
+    // the first fetch is already cancelled
+    assert!(first.send(Ok("first".into())).is_err());
+    pool.run_until_stalled();
+    assert_eq!(resource.latest(), Some("second".into()));
+}
+
+#[test]
+fn should_keep_latest_on_error_and_refetch() {
+    let mut pool = LocalPool::new();
+    let responders = Responders::default();
+
+    let resource = Resource::new(spawner(&pool), || 1, manual_fetcher(&responders));
+    let (_, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Ok("ok".into())).unwrap();
+    pool.run_until_stalled();
+
+    resource.refetch();
+    assert!(resource.loading());
+    let (_, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Err("failed".into())).unwrap();
+    pool.run_until_stalled();
+    assert!(!resource.loading());
+    assert_eq!(resource.error(), Some("failed".into()));
+    assert_eq!(resource.latest(), Some("ok".into()));
+
+    resource.refetch();
+    let (_, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Ok("ok again".into())).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(resource.error(), None);
+    assert_eq!(resource.latest(), Some("ok again".into()));
+}
+
+#[test]
+fn should_be_read_reactively() {
+    let mut pool = LocalPool::new();
+    let responders = Responders::default();
+    let resource = Resource::new(spawner(&pool), || 1, manual_fetcher(&responders));
+
+    let observed = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || {
+            let state = (resource.loading(), resource.with_latest(|v| v.cloned()));
+            observed.borrow_mut().push(state);
+        }
+    });
+
+    let (_, tx) = responders.borrow_mut().pop().unwrap();
+    tx.send(Ok("value".into())).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(
+        *observed.borrow(),
+        [(true, None), (false, Some("value".into()))]
+    );
+}
+
+#[test]
+fn should_cancel_on_dispose() {
+    let mut pool = LocalPool::new();
+    let responders = Responders::default();
+    let resource = Resource::new(spawner(&pool), || 1, manual_fetcher(&responders));
+    pool.run_until_stalled();
+
+    resource.dispose();
+    pool.run_until_stalled();
+    let (_, tx) = responders.borrow_mut().pop().unwrap();
+    assert!(tx.send(Ok("value".into())).is_err());
+}
@@ -0,0 +1,100 @@
+use alien_signals::{Computed, Effect, Error, NodeKind, Runtime, Signal};
+
+#[test]
+fn should_isolate_reactive_graphs() {
+    let runtime1 = Runtime::new();
+    let runtime2 = Runtime::new();
+
+    let triggers = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+
+    let src1 = runtime1.enter(|| {
+        let src = Signal::new(0);
+        Effect::new({
+            let triggers = triggers.clone();
+            move || triggers.lock().unwrap().push((1, src.get()))
+        });
+        src
+    });
+    let src2 = runtime2.enter(|| {
+        let src = Signal::new(0);
+        Effect::new({
+            let triggers = triggers.clone();
+            move || triggers.lock().unwrap().push((2, src.get()))
+        });
+        src
+    });
+
+    runtime1.enter(|| src1.set(1));
+    runtime2.enter(|| {
+        alien_signals::start_batch();
+        src2.set(1);
+        // batch of runtime2 doesn't affect runtime1
+        runtime1.enter(|| src1.set(2));
+        alien_signals::end_batch();
+    });
+    assert_eq!(
+        triggers.lock().unwrap().as_slice(),
+        [(1, 0), (2, 0), (1, 1), (1, 2), (2, 1)]
+    );
+}
+
+#[test]
+fn should_free_all_nodes_on_drop() {
+    let captured = std::rc::Rc::new(());
+
+    let runtime = Runtime::new();
+    let (src, c) = runtime.enter(|| {
+        let src = Signal::new(1);
+        let c = Computed::new({
+            let captured = captured.clone();
+            move |_| {
+                let _ = &captured;
+                src.get() + 1
+            }
+        });
+        Effect::new({
+            let captured = captured.clone();
+            move || {
+                let _ = &captured;
+                let _ = c.get();
+            }
+        });
+        (src, c)
+    });
+    assert_eq!(std::rc::Rc::strong_count(&captured), 3);
+
+    drop(runtime);
+    assert_eq!(std::rc::Rc::strong_count(&captured), 1);
+
+    // handles of the dropped runtime are detected
+    assert_eq!(src.try_get(), Err(Error::WrongRuntime(NodeKind::Signal)));
+    assert_eq!(c.try_get(), Err(Error::WrongRuntime(NodeKind::Computed)));
+    // new runtime never takes the place of the dropped one
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let _ = Signal::new(0);
+        assert_eq!(src.try_get(), Err(Error::WrongRuntime(NodeKind::Signal)));
+    });
+}
+
+#[test]
+#[should_panic(expected = "this `Signal` is used out of the runtime it belongs to")]
+fn should_panic_on_wrong_runtime() {
+    let runtime = Runtime::new();
+    let src = runtime.enter(|| Signal::new(0));
+    src.set(1);
+}
+
+#[test]
+fn should_restore_previous_runtime_on_panic() {
+    let src = Signal::new(0);
+
+    let runtime = Runtime::new();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        runtime.enter(|| panic!("in runtime"))
+    }));
+    assert!(result.is_err());
+
+    src.set(1);
+    assert_eq!(src.get(), 1);
+}
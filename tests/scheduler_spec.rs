@@ -0,0 +1,146 @@
+use alien_signals::{
+    Effect, FlushRequest, Runtime, Signal, batch, flush_effects, remove_scheduler, set_scheduler,
+};
+
+type Requests = std::rc::Rc<std::cell::RefCell<Vec<FlushRequest>>>;
+
+fn collect_requests() -> Requests {
+    let requests = Requests::default();
+    set_scheduler({
+        let requests = requests.clone();
+        move |request| requests.borrow_mut().push(request)
+    });
+    requests
+}
+
+fn observe(src: Signal<i32>) -> std::rc::Rc<std::cell::RefCell<Vec<i32>>> {
+    let observed = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || observed.borrow_mut().push(src.get())
+    });
+    observed
+}
+
+#[test]
+fn should_defer_effects_until_flushed() {
+    let requests = collect_requests();
+    let src = Signal::new(0);
+    let observed = observe(src);
+    assert_eq!(*observed.borrow(), [0]);
+
+    src.set(1);
+    src.set(2);
+    assert_eq!(*observed.borrow(), [0]);
+    // coalesced
+    assert_eq!(requests.borrow().len(), 1);
+
+    requests.take().into_iter().for_each(FlushRequest::flush);
+    assert_eq!(*observed.borrow(), [0, 2]);
+
+    src.set(3);
+    assert_eq!(requests.borrow().len(), 1);
+    flush_effects();
+    assert_eq!(*observed.borrow(), [0, 2, 3]);
+}
+
+#[test]
+fn should_request_once_per_batch() {
+    let requests = collect_requests();
+    let a = Signal::new(0);
+    let b = Signal::new(0);
+    let observed_a = observe(a);
+    let observed_b = observe(b);
+
+    batch(|| {
+        a.set(1);
+        b.set(1);
+    });
+    assert_eq!(requests.borrow().len(), 1);
+    assert_eq!(
+        (observed_a.borrow().len(), observed_b.borrow().len()),
+        (1, 1)
+    );
+
+    flush_effects();
+    assert_eq!(*observed_a.borrow(), [0, 1]);
+    assert_eq!(*observed_b.borrow(), [0, 1]);
+}
+
+#[test]
+fn should_not_request_without_queued_effects() {
+    let requests = collect_requests();
+    let src = Signal::new(0);
+    src.set(1);
+    assert!(requests.borrow().is_empty());
+}
+
+#[test]
+fn should_run_synchronously_after_removing_scheduler() {
+    let requests = collect_requests();
+    let src = Signal::new(0);
+    let observed = observe(src);
+
+    src.set(1);
+    remove_scheduler();
+    src.set(2);
+    // the pending one is flushed together
+    assert_eq!(*observed.borrow(), [0, 2]);
+    assert_eq!(requests.borrow().len(), 1);
+}
+
+#[test]
+fn should_set_scheduler_per_runtime() {
+    let requests = collect_requests();
+
+    let runtime = Runtime::new();
+    runtime.enter(|| {
+        let src = Signal::new(0);
+        let observed = observe(src);
+        src.set(1);
+        assert_eq!(*observed.borrow(), [0, 1]);
+    });
+    assert!(requests.borrow().is_empty());
+}
+
+#[test]
+fn should_request_again_after_dropped_request() {
+    let requests = collect_requests();
+    let src = Signal::new(0);
+    let observed = observe(src);
+
+    src.set(1);
+    assert_eq!(requests.borrow().len(), 1);
+    drop(requests.take());
+
+    src.set(2);
+    assert_eq!(requests.borrow().len(), 1);
+    requests.take().into_iter().for_each(FlushRequest::flush);
+    assert_eq!(*observed.borrow(), [0, 2]);
+}
+
+#[test]
+fn should_request_again_after_panic_in_scheduler() {
+    let panics = std::rc::Rc::new(std::cell::Cell::new(true));
+    let requests = Requests::default();
+    set_scheduler({
+        let panics = panics.clone();
+        let requests = requests.clone();
+        move |request| {
+            if panics.replace(false) {
+                panic!("failed");
+            }
+            requests.borrow_mut().push(request)
+        }
+    });
+    let src = Signal::new(0);
+    let observed = observe(src);
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| src.set(1)));
+    assert!(panicked.is_err());
+
+    src.set(2);
+    assert_eq!(requests.borrow().len(), 1);
+    requests.take().into_iter().for_each(FlushRequest::flush);
+    assert_eq!(*observed.borrow(), [0, 2]);
+}
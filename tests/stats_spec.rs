@@ -0,0 +1,109 @@
+use alien_signals::{Computed, Effect, EffectScope, Runtime, Signal, Stats, batch, stats};
+
+fn in_new_runtime(f: impl FnOnce()) {
+    Runtime::new().enter(f);
+}
+
+#[test]
+fn should_count_live_nodes_and_links() {
+    in_new_runtime(|| {
+        assert_eq!(stats(), Stats::default());
+
+        let a = Signal::new(1);
+        let b = Signal::new(2);
+        let sum = Computed::new(move |_| a.get() + b.get());
+        let scope = EffectScope::new(move || {
+            Effect::new(move || {
+                sum.get();
+            });
+        });
+        let s = stats();
+        assert_eq!(
+            (s.signals, s.computeds, s.effects, s.effect_scopes),
+            (2, 1, 1, 1)
+        );
+        // a -> sum, b -> sum, sum -> effect, effect -> scope
+        assert_eq!(s.links, 4);
+        assert_eq!((s.node_chunks, s.link_chunks), (1, 1));
+        assert!(s.arena_bytes > 0);
+
+        scope.dispose();
+        let s = stats();
+        assert_eq!(
+            (s.signals, s.computeds, s.effects, s.effect_scopes),
+            (2, 1, 0, 0)
+        );
+        assert_eq!(s.links, 0);
+    });
+}
+
+#[test]
+fn should_count_recomputations_and_effect_runs() {
+    in_new_runtime(|| {
+        let x = Signal::new(1);
+        let c1 = Computed::new(move |_| x.get() + 1);
+        let c2 = Computed::new(move |_| c1.get() * 2);
+        let c3 = Computed::new(move |_| x.get() % 2);
+        let c4 = Computed::new(move |_| c3.get() + 100);
+        let _e = Effect::new(move || {
+            c2.get();
+            c4.get();
+        });
+        let s = stats();
+        assert_eq!((s.recomputations, s.effect_runs), (4, 1));
+
+        // `c3` doesn't change, so `c4` is not recomputed
+        let before = stats();
+        x.set(3);
+        let after = stats();
+        assert_eq!(after.recomputations - before.recomputations, 3);
+        assert_eq!(after.effect_runs - before.effect_runs, 1);
+        assert!(after.check_dirty_walks > before.check_dirty_walks);
+        assert_eq!(after.flushes - before.flushes, 1);
+    });
+}
+
+#[test]
+fn should_count_flushes_once_per_batch() {
+    in_new_runtime(|| {
+        let a = Signal::new(0);
+        let _e = Effect::new(move || {
+            a.get();
+        });
+
+        let before = stats();
+        batch(|| {
+            a.set(1);
+            a.set(2);
+        });
+        let after = stats();
+        assert_eq!(after.flushes - before.flushes, 1);
+        assert_eq!(after.effect_runs - before.effect_runs, 1);
+    });
+}
+
+#[test]
+fn should_count_per_runtime() {
+    in_new_runtime(|| {
+        let _a = Signal::new(0);
+        in_new_runtime(|| assert_eq!(stats().signals, 0));
+        assert_eq!(stats().signals, 1);
+    });
+}
+
+#[test]
+fn should_allocate_chunks_on_demand() {
+    in_new_runtime(|| {
+        let signals = (0..1500).map(Signal::new).collect::<Vec<_>>();
+        let s = stats();
+        assert_eq!(s.signals, 1500);
+        assert_eq!(s.node_chunks, 2);
+
+        // slots are reused, and chunks are kept
+        signals.into_iter().for_each(Signal::dispose);
+        let _reused = Signal::new(0);
+        let s = stats();
+        assert_eq!(s.signals, 1);
+        assert_eq!(s.node_chunks, 2);
+    });
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "stream")]
+
+mod common;
+
+use alien_signals::inspect::AsNodeId;
+use alien_signals::{Computed, Effect, Signal, batch};
+use common::spawner;
+use futures::channel::mpsc;
+use futures::executor::{LocalPool, block_on};
+use futures::task::LocalSpawnExt;
+use futures::{Stream, StreamExt};
+
+#[test]
+fn should_yield_settled_values_of_signal() {
+    let count = Signal::new(1);
+    let mut stream = count.to_stream();
+    assert_eq!(block_on(stream.next()), Some(1));
+
+    count.set(2);
+    count.set(2);
+    batch(|| {
+        count.set(3);
+        count.set(4);
+    });
+    assert_eq!(stream.size_hint().0, 2);
+    assert_eq!(block_on(stream.next()), Some(2));
+    assert_eq!(block_on(stream.next()), Some(4));
+
+    count.dispose();
+    assert_eq!(block_on(stream.next()), None);
+}
+
+#[test]
+fn should_yield_settled_values_of_computed() {
+    let a = Signal::new(1);
+    let b = Signal::new(10);
+    let sum = Computed::new(move |_| a.get() + b.get());
+    let stream = sum.to_stream();
+
+    batch(|| {
+        a.set(2);
+        b.set(9); // still 11
+    });
+    a.set(3);
+    sum.dispose();
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), [11, 12]);
+}
+
+#[test]
+fn should_wake_waiting_task() {
+    let mut pool = LocalPool::new();
+    let count = Signal::new(0);
+    let received = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+
+    pool.spawner()
+        .spawn_local({
+            let received = received.clone();
+            count.to_stream().for_each(move |value| {
+                received.borrow_mut().push(value);
+                async {}
+            })
+        })
+        .unwrap();
+    pool.run_until_stalled();
+    assert_eq!(*received.borrow(), [0]);
+
+    count.set(1);
+    count.set(2);
+    pool.run_until_stalled();
+    assert_eq!(*received.borrow(), [0, 1, 2]);
+
+    // the task completes when the signal is disposed
+    count.dispose();
+    pool.run();
+    assert_eq!(*received.borrow(), [0, 1, 2]);
+}
+
+#[test]
+fn should_stop_watching_when_stream_dropped() {
+    let a = Signal::new(1);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+    let double = Computed::new({
+        let runs = runs.clone();
+        move |_| {
+            runs.set(runs.get() + 1);
+            a.get() * 2
+        }
+    });
+
+    let stream = double.to_stream();
+    a.set(2);
+    assert_eq!(runs.get(), 2);
+
+    drop(stream);
+    a.set(3);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn should_not_be_owned_by_outer_effect() {
+    let trigger = Signal::new(0);
+    let count = Signal::new(0);
+    let stream = std::rc::Rc::new(std::cell::RefCell::new(None));
+
+    let e = Effect::new({
+        let stream = stream.clone();
+        move || {
+            trigger.get();
+            stream.borrow_mut().get_or_insert_with(|| count.to_stream());
+        }
+    });
+    trigger.set(1);
+    e.dispose();
+
+    count.set(1);
+    count.dispose();
+    let stream = stream.borrow_mut().take().unwrap();
+    assert_eq!(block_on(stream.collect::<Vec<_>>()), [0, 1]);
+}
+
+#[test]
+fn should_set_signal_from_stream() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = mpsc::unbounded();
+
+    let latest = Signal::from_stream(spawner(&pool), rx, 0);
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let _e = Effect::new({
+        let seen = seen.clone();
+        move || seen.borrow_mut().push(latest.get())
+    });
+    pool.run_until_stalled();
+    assert_eq!(latest.get(), 0);
+
+    tx.unbounded_send(1).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(latest.get(), 1);
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(latest.get(), 2);
+    assert_eq!(*seen.borrow(), [0, 1, 2]);
+
+    // the stream ending keeps the last value
+    assert_eq!(latest.node_id().cleanup_count(), 1);
+    drop(tx);
+    pool.run();
+    assert_eq!(latest.get(), 2);
+    assert_eq!(latest.node_id().cleanup_count(), 0);
+}
+
+#[test]
+fn should_drop_stream_when_signal_disposed() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+
+    let latest = Signal::from_stream(spawner(&pool), rx, 0);
+    pool.run_until_stalled();
+    assert!(!tx.is_closed());
+
+    latest.dispose();
+    pool.run_until_stalled();
+    assert!(tx.is_closed());
+    assert!(tx.unbounded_send(1).is_err());
+}
+
+#[test]
+fn should_not_pile_up_cleanups_on_source() {
+    let count = Signal::new(0);
+    let double = Computed::new(move |_| count.get() * 2);
+    for i in 0..10 {
+        let mut stream = count.to_stream();
+        assert_eq!(block_on(stream.next()), Some(i));
+        let mut stream = double.to_stream();
+        assert_eq!(block_on(stream.next()), Some(i * 2));
+        count.set(i + 1);
+    }
+    assert_eq!(count.node_id().cleanup_count(), 0);
+    assert_eq!(double.node_id().cleanup_count(), 0);
+
+    let _stream = count.to_stream();
+    let _stream = double.to_stream();
+    count.set(100);
+    assert_eq!(count.node_id().cleanup_count(), 1);
+    assert_eq!(double.node_id().cleanup_count(), 1);
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "sync")]
+
+use alien_signals::sync::{Computed, Effect, Signal};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn should_share_signal_and_computed_across_threads() {
+    let count = Signal::new(0);
+    let double = Computed::new(move |_| count.get() * 2);
+
+    let handles = (0..8)
+        .map(|_| {
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    count.set_with(|c| c + 1);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(count.get(), 800);
+    assert_eq!(double.get(), 1600);
+}
+
+#[test]
+fn should_run_effect_on_the_setting_thread() {
+    let src = Signal::new(0);
+    let ran_on = Arc::new(Mutex::new(vec![]));
+
+    let effect = Effect::new({
+        let ran_on = ran_on.clone();
+        move || {
+            let _ = src.get();
+            ran_on.lock().unwrap().push(std::thread::current().id());
+        }
+    });
+
+    let setter = std::thread::spawn(move || {
+        src.set(1);
+        std::thread::current().id()
+    });
+    let setter_id = setter.join().unwrap();
+
+    assert_eq!(
+        *ran_on.lock().unwrap(),
+        [std::thread::current().id(), setter_id]
+    );
+
+    effect.dispose();
+    src.set(2);
+    assert_eq!(ran_on.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn should_allow_setting_signals_in_effect() {
+    let src = Signal::new(0);
+    let mirror = Signal::new(0);
+
+    let _effect = Effect::new(move || mirror.set(src.get()));
+
+    std::thread::spawn(move || src.set(42)).join().unwrap();
+    assert_eq!(mirror.get(), 42);
+}
+
+#[test]
+fn should_detect_use_of_disposed_signal() {
+    let src = Signal::new(1);
+    src.dispose();
+    assert_eq!(
+        std::thread::spawn(move || src.try_get()).join().unwrap(),
+        Err(alien_signals::Error::Disposed(
+            alien_signals::NodeKind::Signal
+        ))
+    );
+}
+
+#[test]
+fn should_panic_on_thread_local_apis_in_shared_runtime() {
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_err();
+        payload.downcast::<String>().map(|s| *s).unwrap_or_default()
+    }
+
+    let message = panic_message(|| {
+        alien_signals::sync::untracked(|| {
+            alien_signals::Signal::new(std::rc::Rc::new(0));
+        })
+    });
+    assert!(message.starts_with(
+        "`Signal::new` can't be used in getters, effects and cleanups of `sync` handles"
+    ));
+
+    let message = panic_message(|| {
+        alien_signals::sync::batch(|| alien_signals::on_cleanup(|| ()));
+    });
+    assert!(message.starts_with("`on_cleanup` can't be used"));
+
+    // the `sync` ones are still available, and thread-local ones out of the shared runtime
+    let src = Signal::new(1);
+    let double = alien_signals::sync::untracked(|| Computed::new(move |_| src.get() * 2));
+    assert_eq!(double.get(), 2);
+    assert_eq!(
+        alien_signals::Signal::new(std::rc::Rc::new(3)).get(),
+        std::rc::Rc::new(3)
+    );
+}
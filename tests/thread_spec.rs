@@ -0,0 +1,37 @@
+use alien_signals::{Computed, Effect, Signal};
+
+#[test]
+fn should_isolate_runtimes_per_thread() {
+    let handles = (0..8)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let src = Signal::new(i);
+                let double = Computed::new(move |_| src.get() * 2);
+
+                let triggers = std::rc::Rc::new(std::sync::Mutex::new(0));
+                Effect::new({
+                    let triggers = triggers.clone();
+                    move || {
+                        let _ = double.get();
+                        *triggers.lock().unwrap() += 1;
+                    }
+                });
+
+                for n in 0..1000 {
+                    alien_signals::start_batch();
+                    src.set(i + n);
+                    alien_signals::end_batch();
+                }
+                assert_eq!(alien_signals::get_batch_depth(), 0);
+                assert!(alien_signals::get_active_sub().is_none());
+
+                (double.get(), *triggers.lock().unwrap())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let i = i as i32;
+        assert_eq!(handle.join().unwrap(), ((i + 999) * 2, 1000));
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "tracing")]
+
+use alien_signals::{Computed, Effect, Signal};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+type Fields = Vec<(&'static str, String)>;
+
+/// collects spans and events as `(name, fields)`
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct Visitor<'f>(&'f mut Fields);
+impl Visit for Visitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let mut fields = Fields::new();
+        attrs.record(&mut Visitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((attrs.metadata().name(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+    fn record(&self, id: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut Visitor(&mut spans[id.into_u64() as usize - 1].1));
+    }
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+    fn enter(&self, _: &Id) {}
+    fn exit(&self, _: &Id) {}
+}
+
+impl Recorder {
+    fn spans_named(&self, name: &str) -> Vec<Fields> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(span, _)| *span == name)
+            .map(|(_, fields)| fields.clone())
+            .collect()
+    }
+}
+
+fn fields(fields: &[(&'static str, &str)]) -> Fields {
+    fields.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
+
+#[test]
+fn should_trace_fan_out_of_set() {
+    let recorder = Recorder::default();
+    let a = Signal::new(1).named("a");
+    let double = Computed::new(move |_| a.get() * 2).named("double");
+    let parity = Computed::new(move |_| a.get() % 2).named("parity");
+    let _e1 = Effect::new(move || {
+        double.get();
+    })
+    .named("e1");
+    let _e2 = Effect::new(move || {
+        parity.get();
+    })
+    .named("e2");
+
+    tracing::subscriber::with_default(recorder.clone(), || a.set(3));
+
+    assert_eq!(
+        recorder.spans_named("propagate"),
+        [fields(&[("node", "a")])]
+    );
+    assert_eq!(
+        recorder.spans_named("update_signal"),
+        [fields(&[("node", "a"), ("changed", "true")])]
+    );
+    assert_eq!(
+        recorder.spans_named("update_computed"),
+        [
+            fields(&[("node", "double"), ("changed", "true")]),
+            fields(&[("node", "parity"), ("changed", "false")]),
+        ]
+    );
+    assert_eq!(
+        recorder.spans_named("run_effect"),
+        [
+            fields(&[("node", "e1"), ("skipped", "false")]),
+            fields(&[("node", "e2"), ("skipped", "true")]),
+        ]
+    );
+    assert_eq!(
+        recorder.spans_named("check_dirty"),
+        [
+            fields(&[("node", "e1"), ("dirty", "true")]),
+            fields(&[("node", "e2"), ("dirty", "false")]),
+        ]
+    );
+    assert_eq!(recorder.spans_named("flush"), [fields(&[("effects", "2")])]);
+}
+
+#[test]
+fn should_trace_skipped_recomputation() {
+    let recorder = Recorder::default();
+    let a = Signal::new(1);
+    let parity = Computed::new(move |_| a.get() % 2);
+    let label = Computed::new(move |_| format!("parity {}", parity.get())).named("label");
+    let _e = Effect::new(move || {
+        label.get();
+    });
+
+    tracing::subscriber::with_default(recorder.clone(), || a.set(3));
+
+    let events = recorder.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        [fields(&[
+            ("message", "deps unchanged, skipped recomputation"),
+            ("node", "label"),
+        ])]
+    );
+    // unnamed nodes are labelled with their kinds
+    let updated = recorder.spans_named("update_computed");
+    assert_eq!(updated.len(), 1);
+    assert!(updated[0][0].1.starts_with("Computed@0x"));
+}
@@ -99,3 +99,66 @@ fn should_dispose_computed_in_effect_scope() {
     src.set(3);
     assert_eq!(*effect_trigger_times.lock().unwrap(), 1);
 }
+
+#[test]
+fn should_not_track_in_untracked() {
+    let src = Signal::new(0);
+    let other = Signal::new(0);
+
+    let effect_trigger_times = std::rc::Rc::new(std::sync::Mutex::new(0));
+    Effect::new({
+        let effect_trigger_times = effect_trigger_times.clone();
+        move || {
+            *effect_trigger_times.lock().unwrap() += 1;
+            let _ = other.get();
+            let _ = alien_signals::untracked(|| src.get() + src.peek());
+        }
+    });
+    assert_eq!(*effect_trigger_times.lock().unwrap(), 1);
+
+    src.set(1);
+    assert_eq!(*effect_trigger_times.lock().unwrap(), 1);
+
+    // still tracking `other` after `untracked`
+    other.set(1);
+    assert_eq!(*effect_trigger_times.lock().unwrap(), 2);
+}
+
+#[test]
+fn should_peek_computed_without_subscribing() {
+    let src = Signal::new(1);
+    let double = Computed::new(move |_| src.get() * 2);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || observed.lock().unwrap().push(double.peek())
+    });
+
+    src.set(2);
+    assert_eq!(*observed.lock().unwrap(), [2]);
+    assert_eq!(double.peek(), 4);
+}
+
+#[test]
+fn should_restore_active_sub_on_panic_in_untracked() {
+    let src = Signal::new(0);
+
+    let effect_trigger_times = std::rc::Rc::new(std::sync::Mutex::new(0));
+    Effect::new({
+        let effect_trigger_times = effect_trigger_times.clone();
+        move || {
+            *effect_trigger_times.lock().unwrap() += 1;
+            let panicked = std::panic::catch_unwind(|| {
+                alien_signals::untracked(|| panic!("in untracked"));
+            });
+            assert!(panicked.is_err());
+            let _ = src.get();
+        }
+    });
+    assert_eq!(*effect_trigger_times.lock().unwrap(), 1);
+
+    src.set(1);
+    assert_eq!(*effect_trigger_times.lock().unwrap(), 2);
+    assert!(alien_signals::get_active_sub().is_none());
+}
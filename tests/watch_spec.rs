@@ -0,0 +1,147 @@
+use alien_signals::{Signal, WatchOptions, watch};
+
+type Calls = std::rc::Rc<std::cell::RefCell<Vec<(i32, Option<i32>)>>>;
+
+fn record(calls: &Calls) -> impl Fn(&i32, Option<&i32>) + 'static {
+    let calls = calls.clone();
+    move |new, old| calls.borrow_mut().push((*new, old.copied()))
+}
+
+#[test]
+fn should_call_back_with_new_and_old_values() {
+    let src = Signal::new(1);
+    let calls = Calls::default();
+
+    let watcher = watch(move || src.get(), record(&calls), WatchOptions::default());
+    assert!(calls.borrow().is_empty());
+
+    src.set(2);
+    src.set(3);
+    assert_eq!(*calls.borrow(), [(2, Some(1)), (3, Some(2))]);
+
+    watcher.dispose();
+    src.set(4);
+    assert_eq!(calls.borrow().len(), 2);
+}
+
+#[test]
+fn should_call_back_immediately() {
+    let src = Signal::new(1);
+    let calls = Calls::default();
+
+    watch(
+        move || src.get(),
+        record(&calls),
+        WatchOptions {
+            immediate: true,
+            ..Default::default()
+        },
+    );
+    src.set(2);
+    assert_eq!(*calls.borrow(), [(1, None), (2, Some(1))]);
+}
+
+#[test]
+fn should_call_back_once() {
+    let src = Signal::new(1);
+    let calls = Calls::default();
+
+    watch(
+        move || src.get(),
+        record(&calls),
+        WatchOptions {
+            once: true,
+            ..Default::default()
+        },
+    );
+    src.set(2);
+    src.set(3);
+    assert_eq!(*calls.borrow(), [(2, Some(1))]);
+
+    let calls = Calls::default();
+    watch(
+        move || src.get(),
+        record(&calls),
+        WatchOptions {
+            immediate: true,
+            once: true,
+            ..Default::default()
+        },
+    );
+    src.set(4);
+    assert_eq!(*calls.borrow(), [(3, None)]);
+}
+
+#[test]
+fn should_skip_equal_values() {
+    let src = Signal::new(1);
+    let calls = Calls::default();
+
+    watch(
+        move || src.get(),
+        record(&calls),
+        WatchOptions {
+            equals: Box::new(|a, b| a / 10 == b / 10),
+            ..Default::default()
+        },
+    );
+    src.set(5);
+    src.set(12);
+    src.set(15);
+    assert_eq!(*calls.borrow(), [(12, Some(1))]);
+}
+
+#[test]
+fn should_run_callback_through_scheduler() {
+    let src = Signal::new(1);
+    let calls = Calls::default();
+    let jobs = std::rc::Rc::new(std::cell::RefCell::new(Vec::<Box<dyn FnOnce()>>::new()));
+
+    let watcher = watch(
+        move || src.get(),
+        record(&calls),
+        WatchOptions {
+            scheduler: Box::new({
+                let jobs = jobs.clone();
+                move |job| jobs.borrow_mut().push(job)
+            }),
+            ..Default::default()
+        },
+    );
+    src.set(2);
+    src.set(3);
+    assert!(calls.borrow().is_empty());
+
+    let pending = jobs.borrow_mut().drain(..).collect::<Vec<_>>();
+    pending.into_iter().for_each(|job| job());
+    assert_eq!(*calls.borrow(), [(2, Some(1)), (3, Some(2))]);
+
+    // jobs of a disposed watcher do nothing
+    src.set(4);
+    watcher.dispose();
+    let pending = jobs.borrow_mut().drain(..).collect::<Vec<_>>();
+    pending.into_iter().for_each(|job| job());
+    assert_eq!(calls.borrow().len(), 2);
+}
+
+#[test]
+fn should_not_track_reads_in_callback() {
+    let src = Signal::new(1);
+    let other = Signal::new(0);
+    let calls = Calls::default();
+
+    watch(
+        move || src.get(),
+        {
+            let record = record(&calls);
+            move |new, old| {
+                let _ = other.get();
+                record(new, old);
+            }
+        },
+        WatchOptions::default(),
+    );
+    src.set(2);
+    other.set(1);
+    assert_eq!(*calls.borrow(), [(2, Some(1))]);
+}
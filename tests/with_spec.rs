@@ -0,0 +1,86 @@
+use alien_signals::{Computed, Effect, Signal};
+
+#[test]
+fn should_read_by_reference_and_track() {
+    let items = Signal::new(vec![1, 2, 3]);
+    let sum = Computed::new(move |_| items.with(|items| items.iter().sum::<i32>()));
+    assert_eq!(sum.get(), 6);
+
+    let observed = std::rc::Rc::new(std::sync::Mutex::new(vec![]));
+    Effect::new({
+        let observed = observed.clone();
+        move || {
+            sum.with(|sum| observed.lock().unwrap().push(*sum));
+        }
+    });
+
+    items.set_mut(|items| items.push(4));
+    assert_eq!(*observed.lock().unwrap(), [6, 10]);
+}
+
+#[test]
+fn should_not_clone_value() {
+    #[derive(PartialEq)]
+    struct CountClone(std::rc::Rc<std::cell::Cell<usize>>);
+    impl Clone for CountClone {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            Self(self.0.clone())
+        }
+    }
+
+    let clones = std::rc::Rc::new(std::cell::Cell::new(0));
+    let src = Signal::new(CountClone(clones.clone()));
+    let c = Computed::new(move |_| src.with(|v| v.0.get()));
+    let before = clones.get();
+
+    assert!(src.with(|v| std::rc::Rc::ptr_eq(&v.0, &clones)));
+    assert_eq!(c.with(|n| *n), before);
+    assert_eq!(clones.get(), before);
+}
+
+#[test]
+fn should_allow_reading_other_signals_in_with() {
+    let a = Signal::new(1);
+    let b = Signal::new(2);
+    let c = Computed::new(move |_| a.get() + b.get());
+    let d = Computed::new(move |_| c.get() * 10);
+
+    let r = a.with(|a_value| {
+        // reads `a` again and a computed depending on `a` in `with` of `a`
+        *a_value + a.get() + c.with(|c| *c) + d.get()
+    });
+    assert_eq!(r, 1 + 1 + 3 + 30);
+
+    b.set(3);
+    assert_eq!(c.with(|c| *c + d.get()), 4 + 40);
+}
+
+#[test]
+#[should_panic(expected = "this `Signal` can't be updated while it's being read")]
+fn should_panic_on_setting_signal_in_its_with() {
+    let src = Signal::new(1);
+    src.with(|v| src.set(*v + 1));
+}
+
+#[test]
+#[should_panic(expected = "this `Signal` can't be read while it's being updated")]
+fn should_panic_on_getting_signal_in_its_set_mut() {
+    let src = Signal::new(1);
+    src.set_mut(|v| *v += src.get());
+}
+
+#[test]
+#[should_panic(expected = "this `Computed` is read while computing itself: Computed@0x")]
+fn should_panic_on_reading_computed_in_its_getter() {
+    let this = std::rc::Rc::new(std::cell::Cell::new(None::<Computed<i32>>));
+    let c = Computed::new({
+        let this = this.clone();
+        move |_| match this.get() {
+            Some(this) => this.with(|v| *v),
+            None => 0,
+        }
+    });
+    this.set(Some(c));
+    let _ = c.get();
+}